@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use clap::Parser;
-use std::io::{self, BufRead, Write};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::ExitCode;
 
 #[derive(Parser, Debug)]
 #[command(name = "cat")]
@@ -19,16 +20,56 @@ struct Args {
     #[arg(short = 'b', long = "number-nonblank")]
     number_nonblank: bool,
 
+    /// Pad line numbers to N columns
+    #[arg(
+        long = "number-width",
+        value_name = "N",
+        default_value_t = NumberFormat::default().width
+    )]
+    number_width: usize,
+
+    /// Print SEP between a line number and its line [default: a tab]
+    #[arg(long = "number-separator", value_name = "SEP")]
+    number_separator: Option<String>,
+
     /// Show all characters (equivalent to -vET)
     #[arg(short = 'A', long = "show-all")]
     show_all: bool,
 
+    /// Display $ at the end of each line
+    #[arg(short = 'E', long = "show-ends")]
+    show_ends: bool,
+
+    /// Display tabs as ^I
+    #[arg(short = 'T', long = "show-tabs")]
+    show_tabs: bool,
+
+    /// Use ^ and M- notation for control and high bytes, except tabs and line ends
+    #[arg(short = 'v', long = "show-nonprinting")]
+    show_nonprinting: bool,
+
     /// Squeeze multiple adjacent blank lines into one
     #[arg(short = 's', long = "squeeze-blank")]
     squeeze_blank: bool,
+
+    /// Convert CRLF line endings to LF
+    #[arg(long = "dos2unix", conflicts_with = "unix2dos")]
+    dos2unix: bool,
+
+    /// Convert LF line endings to CRLF
+    #[arg(long = "unix2dos")]
+    unix2dos: bool,
+
+    /// Print binary files to a terminal instead of skipping them
+    #[arg(long = "binary")]
+    binary: bool,
+}
+
+fn main() -> ExitCode {
+    common::exit::report("cat", run())
 }
 
-fn main() -> Result<()> {
+fn run() -> Result<()> {
     let args = Args::parse();
     
     // -b overrides -n
@@ -40,10 +81,38 @@ fn main() -> Result<()> {
         NumberMode::None
     };
     
-    let mut processor = LineProcessor::new(number_mode, args.show_all, args.squeeze_blank);
+    let show = ShowOptions {
+        ends: args.show_all || args.show_ends,
+        tabs: args.show_all || args.show_tabs,
+        nonprinting: args.show_all || args.show_nonprinting,
+    };
+    
+    let number_format = NumberFormat {
+        width: args.number_width,
+        separator: args.number_separator.unwrap_or(NumberFormat::default().separator),
+    };
+    
+    let line_ending = if args.dos2unix {
+        LineEnding::Unix
+    } else if args.unix2dos {
+        LineEnding::Dos
+    } else {
+        LineEnding::Keep
+    };
+    
+    let mut processor = LineProcessor::new(
+        number_mode,
+        number_format,
+        show,
+        line_ending,
+        args.squeeze_blank,
+    );
+    
+    // Raw binary data garbles a terminal; -v makes it printable
+    let skip_binary = !args.binary && !show.nonprinting && io::stdout().is_terminal();
     
     for file in &args.files {
-        process_file(file, &mut processor)
+        process_file(file, &mut processor, skip_binary)
             .with_context(|| format!("Failed to process file: {}", file))?;
     }
     
@@ -57,27 +126,109 @@ enum NumberMode {
     NonBlank,
 }
 
+/// How line numbers are laid out: right-aligned in `width` columns, then
+/// `separator`. The separator is written as is, whatever `-T` says.
+#[derive(Debug, Clone)]
+struct NumberFormat {
+    width: usize,
+    separator: String,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            width: 6,
+            separator: "\t".to_string(),
+        }
+    }
+}
+
+/// Line endings to convert to, if any.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LineEnding {
+    Keep,
+    /// `\n`: drop the `\r` before each newline
+    Unix,
+    /// `\r\n`: add a `\r` before each newline that lacks one
+    Dos,
+}
+
+/// Which invisible characters to make visible.
+#[derive(Debug, Clone, Copy, Default)]
+struct ShowOptions {
+    /// Mark each line end with `$`
+    ends: bool,
+    /// Show tabs as `^I`
+    tabs: bool,
+    /// Show other control characters as `^X` and high bytes as `M-X`
+    nonprinting: bool,
+}
+
+impl ShowOptions {
+    /// Whether the line's bytes need escaping, as opposed to only a `$`.
+    fn escapes_bytes(&self) -> bool {
+        self.tabs || self.nonprinting
+    }
+}
+
+/// Formats lines for output. One processor handles every file in turn, so
+/// line numbers, `-s` blank runs, and unfinished lines carry over from
+/// the end of one file to the start of the next.
 struct LineProcessor {
     number_mode: NumberMode,
-    show_all: bool,
+    number_format: NumberFormat,
+    show: ShowOptions,
+    line_ending: LineEnding,
     squeeze_blank: bool,
     line_number: usize,
     last_was_blank: bool,
+    /// The last line written had no newline, so the next one carries on
+    /// from it, e.g. into the next file, instead of starting afresh
+    in_line: bool,
 }
 
 impl LineProcessor {
-    fn new(number_mode: NumberMode, show_all: bool, squeeze_blank: bool) -> Self {
+    fn new(
+        number_mode: NumberMode,
+        number_format: NumberFormat,
+        show: ShowOptions,
+        line_ending: LineEnding,
+        squeeze_blank: bool,
+    ) -> Self {
         Self {
             number_mode,
-            show_all,
+            number_format,
+            show,
+            line_ending,
             squeeze_blank,
             line_number: 0,
             last_was_blank: false,
+            in_line: false,
         }
     }
     
-    fn process_line(&mut self, line: &[u8], stdout: &mut impl Write) -> io::Result<()> {
-        let is_blank = line.is_empty() || (line.len() == 1 && line[0] == b'\n');
+    /// Writes one line without its newline. `terminated` says whether the
+    /// input had one, so that a missing newline at the end of a file stays
+    /// missing.
+    fn process_line(
+        &mut self,
+        line: &[u8],
+        terminated: bool,
+        stdout: &mut impl Write,
+    ) -> io::Result<()> {
+        // Converting to LF, a line holding only "\r" counts as blank
+        let line = match line {
+            [rest @ .., b'\r'] if terminated && self.line_ending == LineEnding::Unix => rest,
+            _ => line,
+        };
+        
+        if std::mem::replace(&mut self.in_line, !terminated) {
+            // The rest of a line that has already been numbered
+            self.last_was_blank = false;
+            return self.write_content(line, terminated, stdout);
+        }
+        
+        let is_blank = line.is_empty();
         
         // Handle squeeze blank
         if self.squeeze_blank && is_blank {
@@ -90,78 +241,139 @@ impl LineProcessor {
         }
         
         // Handle line numbering
+        let NumberFormat { width, separator } = &self.number_format;
         match self.number_mode {
             NumberMode::All => {
                 self.line_number += 1;
-                write!(stdout, "{:6}\t", self.line_number)?;
+                write!(stdout, "{:>width$}{}", self.line_number, separator)?;
             }
             NumberMode::NonBlank => {
                 if !is_blank {
                     self.line_number += 1;
-                    write!(stdout, "{:6}\t", self.line_number)?;
+                    write!(stdout, "{:>width$}{}", self.line_number, separator)?;
                 } else {
-                    write!(stdout, "      \t")?;
+                    write!(stdout, "{:width$}{}", "", separator)?;
                 }
             }
             NumberMode::None => {}
         }
         
-        // Process and write the line
-        if self.show_all {
-            self.write_with_show_all(line, stdout)?;
-        } else {
-            stdout.write_all(line)?;
+        self.write_content(line, terminated, stdout)
+    }
+    
+    fn write_content(
+        &self,
+        line: &[u8],
+        terminated: bool,
+        stdout: &mut impl Write,
+    ) -> io::Result<()> {
+        let add_carriage_return =
+            terminated && self.line_ending == LineEnding::Dos && !line.ends_with(b"\r");
+        
+        self.write_bytes(line, stdout)?;
+        if add_carriage_return {
+            self.write_bytes(b"\r", stdout)?;
         }
         
-        stdout.write_all(b"\n")?;
+        if terminated {
+            if self.show.ends {
+                stdout.write_all(b"$")?;
+            }
+            stdout.write_all(b"\n")?;
+        }
         
         Ok(())
     }
     
+    fn write_bytes(&self, bytes: &[u8], stdout: &mut impl Write) -> io::Result<()> {
+        if self.show.escapes_bytes() {
+            self.write_with_show_all(bytes, stdout)
+        } else {
+            stdout.write_all(bytes)
+        }
+    }
+    
     fn write_with_show_all(&self, line: &[u8], stdout: &mut impl Write) -> io::Result<()> {
         for &byte in line {
             match byte {
-                b'\t' => write!(stdout, "^I")?,
-                b'\n' => write!(stdout, "$")?,
-                0..=31 => write!(stdout, "^{}", (byte + 64) as char)?,
-                127 => write!(stdout, "^?")?,
-                128..=255 => write!(stdout, "M-{}", if byte >= 128 + 32 && byte < 127 + 128 {
-                    (byte - 128) as char
-                } else {
-                    '?'
-                })?,
-                _ => stdout.write_all(&[byte])?,
+                b'\t' if self.show.tabs => write!(stdout, "^I")?,
+                _ if !self.show.nonprinting || byte == b'\t' => stdout.write_all(&[byte])?,
+                128..=255 => {
+                    stdout.write_all(b"M-")?;
+                    write_caret(byte & 0x7f, stdout)?;
+                }
+                _ => write_caret(byte, stdout)?,
             }
         }
         Ok(())
     }
 }
 
-fn process_file(filename: &str, processor: &mut LineProcessor) -> Result<()> {
-    let reader = common::io::open_input(filename)?;
+/// Writes a 7-bit byte the way `cat -v` shows it: control characters as
+/// `^@` through `^_`, DEL as `^?`, and anything else as itself. High bytes
+/// get the same treatment after an `M-`, so 0x80 is `M-^@`, 0xE9 is `M-i`,
+/// and 0xFF is `M-^?`.
+fn write_caret(byte: u8, stdout: &mut impl Write) -> io::Result<()> {
+    match byte {
+        0..=31 => write!(stdout, "^{}", (byte + 64) as char),
+        127 => stdout.write_all(b"^?"),
+        _ => stdout.write_all(&[byte]),
+    }
+}
+
+/// Copies one file through `processor`. With `skip_binary`, a file that
+/// looks binary is reported on stderr and left out.
+fn process_file(filename: &str, processor: &mut LineProcessor, skip_binary: bool) -> Result<()> {
+    let mut reader = common::io::open_input(filename)?;
+    
+    if skip_binary && looks_binary(&mut reader)? {
+        eprintln!("cat: {}: is a binary file", filename);
+        return Ok(());
+    }
+    
     let stdout = io::stdout();
     let mut stdout_lock = stdout.lock();
+    let mut line = Vec::new();
     
-    for line_result in reader.split(b'\n') {
-        let line = line_result?;
-        processor.process_line(&line, &mut stdout_lock)?;
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        
+        let terminated = line.last() == Some(&b'\n');
+        if terminated {
+            line.pop();
+        }
+        processor.process_line(&line, terminated, &mut stdout_lock)?;
     }
     
     Ok(())
 }
 
+/// Whether the first buffer of input holds a NUL byte, which text files
+/// don't have. Nothing is consumed.
+fn looks_binary(reader: &mut impl BufRead) -> io::Result<bool> {
+    Ok(reader.fill_buf()?.contains(&0))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Cursor;
 
     #[test]
     fn test_number_mode_all() {
-        let mut processor = LineProcessor::new(NumberMode::All, false, false);
+        let mut processor = LineProcessor::new(
+            NumberMode::All,
+            NumberFormat::default(),
+            ShowOptions::default(),
+            LineEnding::Keep,
+            false,
+        );
         let mut output = Vec::new();
         
-        processor.process_line(b"first", &mut output).unwrap();
-        processor.process_line(b"second", &mut output).unwrap();
+        processor.process_line(b"first", true, &mut output).unwrap();
+        processor.process_line(b"second", true, &mut output).unwrap();
         
         let result = String::from_utf8(output).unwrap();
         assert!(result.contains("     1\tfirst"));
@@ -170,12 +382,18 @@ mod tests {
 
     #[test]
     fn test_number_mode_nonblank() {
-        let mut processor = LineProcessor::new(NumberMode::NonBlank, false, false);
+        let mut processor = LineProcessor::new(
+            NumberMode::NonBlank,
+            NumberFormat::default(),
+            ShowOptions::default(),
+            LineEnding::Keep,
+            false,
+        );
         let mut output = Vec::new();
         
-        processor.process_line(b"first", &mut output).unwrap();
-        processor.process_line(b"", &mut output).unwrap();
-        processor.process_line(b"third", &mut output).unwrap();
+        processor.process_line(b"first", true, &mut output).unwrap();
+        processor.process_line(b"", true, &mut output).unwrap();
+        processor.process_line(b"third", true, &mut output).unwrap();
         
         let result = String::from_utf8(output).unwrap();
         assert!(result.contains("     1\tfirst"));
@@ -183,16 +401,82 @@ mod tests {
         assert!(result.contains("     2\tthird"));
     }
 
+    #[test]
+    fn test_number_format() {
+        let number_format = NumberFormat {
+            width: 3,
+            separator: ": ".to_string(),
+        };
+        let mut processor = LineProcessor::new(
+            NumberMode::NonBlank,
+            number_format,
+            ShowOptions::default(),
+            LineEnding::Keep,
+            false,
+        );
+        let mut output = Vec::new();
+        
+        processor.process_line(b"first", true, &mut output).unwrap();
+        processor.process_line(b"", true, &mut output).unwrap();
+        
+        assert_eq!(output, b"  1: first\n   : \n");
+    }
+
+    #[test]
+    fn test_looks_binary() {
+        let mut text = &b"plain text\n"[..];
+        assert!(!looks_binary(&mut text).unwrap());
+        assert_eq!(text, b"plain text\n");
+        
+        assert!(looks_binary(&mut &b"\x7fELF\x02\x01\x00"[..]).unwrap());
+        assert!(!looks_binary(&mut &b""[..]).unwrap());
+    }
+
+    #[test]
+    fn test_line_ending_conversion() {
+        let show = ShowOptions {
+            ends: true,
+            ..Default::default()
+        };
+        
+        for (line_ending, expected) in [
+            (LineEnding::Unix, &b"a$\nb$\nc\r"[..]),
+            (LineEnding::Dos, &b"a\r$\nb\r$\nc\r"[..]),
+        ] {
+            let mut processor = LineProcessor::new(
+                NumberMode::None,
+                NumberFormat::default(),
+                show,
+                line_ending,
+                false,
+            );
+            let mut output = Vec::new();
+            
+            processor.process_line(b"a\r", true, &mut output).unwrap();
+            processor.process_line(b"b", true, &mut output).unwrap();
+            // Without a newline there is no line ending to convert
+            processor.process_line(b"c\r", false, &mut output).unwrap();
+            
+            assert_eq!(output, expected);
+        }
+    }
+
     #[test]
     fn test_squeeze_blank() {
-        let mut processor = LineProcessor::new(NumberMode::None, false, true);
+        let mut processor = LineProcessor::new(
+            NumberMode::None,
+            NumberFormat::default(),
+            ShowOptions::default(),
+            LineEnding::Keep,
+            true,
+        );
         let mut output = Vec::new();
         
-        processor.process_line(b"first", &mut output).unwrap();
-        processor.process_line(b"", &mut output).unwrap();
-        processor.process_line(b"", &mut output).unwrap();
-        processor.process_line(b"", &mut output).unwrap();
-        processor.process_line(b"second", &mut output).unwrap();
+        processor.process_line(b"first", true, &mut output).unwrap();
+        processor.process_line(b"", true, &mut output).unwrap();
+        processor.process_line(b"", true, &mut output).unwrap();
+        processor.process_line(b"", true, &mut output).unwrap();
+        processor.process_line(b"second", true, &mut output).unwrap();
         
         let result = String::from_utf8(output).unwrap();
         let lines: Vec<&str> = result.lines().collect();
@@ -201,7 +485,18 @@ mod tests {
 
     #[test]
     fn test_show_all_tab() {
-        let processor = LineProcessor::new(NumberMode::None, true, false);
+        let show = ShowOptions {
+            ends: true,
+            tabs: true,
+            nonprinting: true,
+        };
+        let processor = LineProcessor::new(
+            NumberMode::None,
+            NumberFormat::default(),
+            show,
+            LineEnding::Keep,
+            false,
+        );
         let mut output = Vec::new();
         
         processor.write_with_show_all(b"hello\tworld", &mut output).unwrap();
@@ -209,5 +504,73 @@ mod tests {
         let result = String::from_utf8(output).unwrap();
         assert!(result.contains("^I"));
     }
+
+    #[test]
+    fn test_unterminated_line_continues() {
+        let mut processor = LineProcessor::new(
+            NumberMode::All,
+            NumberFormat::default(),
+            ShowOptions::default(),
+            LineEnding::Keep,
+            false,
+        );
+        let mut output = Vec::new();
+        
+        // As when the first file doesn't end in a newline
+        processor.process_line(b"one", false, &mut output).unwrap();
+        processor.process_line(b"two", true, &mut output).unwrap();
+        processor.process_line(b"three", false, &mut output).unwrap();
+        
+        assert_eq!(output, b"     1\tonetwo\n     2\tthree");
+    }
+
+    #[test]
+    fn test_show_nonprinting_high_bytes() {
+        let show = ShowOptions {
+            nonprinting: true,
+            ..Default::default()
+        };
+        let processor = LineProcessor::new(
+            NumberMode::None,
+            NumberFormat::default(),
+            show,
+            LineEnding::Keep,
+            false,
+        );
+        
+        for (byte, expected) in [
+            (0x80, "M-^@"),
+            (0x89, "M-^I"),
+            (0x9f, "M-^_"),
+            (0xa0, "M- "),
+            (0xe9, "M-i"),
+            (0xfe, "M-~"),
+            (0xff, "M-^?"),
+        ] {
+            let mut output = Vec::new();
+            processor.write_with_show_all(&[byte], &mut output).unwrap();
+            assert_eq!(String::from_utf8(output).unwrap(), expected, "byte {:#x}", byte);
+        }
+    }
+
+    #[test]
+    fn test_show_nonprinting_leaves_tabs() {
+        let show = ShowOptions {
+            nonprinting: true,
+            ..Default::default()
+        };
+        let mut processor = LineProcessor::new(
+            NumberMode::None,
+            NumberFormat::default(),
+            show,
+            LineEnding::Keep,
+            false,
+        );
+        let mut output = Vec::new();
+        
+        processor.process_line(b"a\tb\x01\x7f", true, &mut output).unwrap();
+        
+        assert_eq!(output, b"a\tb^A^?\n");
+    }
 }
 
@@ -48,7 +48,7 @@ fn test_cat_number_lines() {
 fn test_cat_number_nonblank() {
     let mut file = NamedTempFile::new().unwrap();
     writeln!(file, "line one").unwrap();
-    writeln!(file, "").unwrap();
+    writeln!(file).unwrap();
     writeln!(file, "line three").unwrap();
     
     let mut cmd = Command::cargo_bin("cat").unwrap();
@@ -63,9 +63,9 @@ fn test_cat_number_nonblank() {
 fn test_cat_squeeze_blank() {
     let mut file = NamedTempFile::new().unwrap();
     writeln!(file, "line one").unwrap();
-    writeln!(file, "").unwrap();
-    writeln!(file, "").unwrap();
-    writeln!(file, "").unwrap();
+    writeln!(file).unwrap();
+    writeln!(file).unwrap();
+    writeln!(file).unwrap();
     writeln!(file, "line five").unwrap();
     
     let mut cmd = Command::cargo_bin("cat").unwrap();
@@ -84,7 +84,26 @@ fn test_cat_nonexistent_file() {
     let mut cmd = Command::cargo_bin("cat").unwrap();
     cmd.arg("nonexistent_file_12345.txt");
     cmd.assert()
-        .failure();
+        .code(1)
+        .stderr(predicate::str::starts_with("cat: "))
+        .stderr(predicate::str::contains("No such file or directory"));
+}
+
+#[test]
+fn test_cat_unknown_option_is_usage_error() {
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("--no-such-option");
+    cmd.assert().code(2);
+}
+
+#[test]
+fn test_cat_debug_prints_error_chain() {
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.env("RUST_CLI_DEBUG", "1").arg("nonexistent_file_12345.txt");
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("Caused by:"))
+        .stderr(predicate::str::contains("NotFound"));
 }
 
 #[test]
@@ -109,3 +128,159 @@ fn test_cat_show_all() {
         .stdout(predicate::str::contains("^I")); // Tab shown as ^I
 }
 
+
+#[test]
+fn test_cat_show_all_marks_tabs_and_line_ends() {
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("-A").write_stdin("a\tb\n\n");
+    cmd.assert().success().stdout("a^Ib$\n$\n");
+}
+
+#[test]
+fn test_cat_show_ends_leaves_tabs_intact() {
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("-E").write_stdin("a\tb\nc\n");
+    cmd.assert().success().stdout("a\tb$\nc$\n");
+}
+
+#[test]
+fn test_cat_show_tabs_leaves_line_ends_untouched() {
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("-T").write_stdin("a\tb\nc\n");
+    cmd.assert().success().stdout("a^Ib\nc\n");
+}
+
+#[test]
+fn test_cat_show_nonprinting_high_bytes() {
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("-v").write_stdin(&b"\x80\xa0\xe9\xff\n"[..]);
+    cmd.assert().success().stdout("M-^@M- M-iM-^?\n");
+}
+
+#[test]
+fn test_cat_keeps_missing_trailing_newline() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "first\nlast").unwrap();
+    
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg(file.path());
+    cmd.assert().success().stdout("first\nlast");
+    
+    // Numbering doesn't add one either
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("-n").arg(file.path());
+    cmd.assert().success().stdout("     1\tfirst\n     2\tlast");
+}
+
+#[test]
+fn test_cat_empty_file_prints_nothing() {
+    let file = NamedTempFile::new().unwrap();
+    
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("-n").arg(file.path());
+    cmd.assert().success().stdout("");
+}
+
+#[test]
+fn test_cat_joins_unterminated_file_to_the_next() {
+    let mut file1 = NamedTempFile::new().unwrap();
+    let mut file2 = NamedTempFile::new().unwrap();
+    write!(file1, "abc").unwrap();
+    writeln!(file2, "def").unwrap();
+    
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("-nE").arg(file1.path()).arg(file2.path());
+    cmd.assert().success().stdout("     1\tabcdef$\n");
+}
+
+#[test]
+fn test_cat_squeeze_blank_across_files() {
+    let mut file1 = NamedTempFile::new().unwrap();
+    let mut file2 = NamedTempFile::new().unwrap();
+    write!(file1, "one\n\n\n").unwrap();
+    write!(file2, "\n\ntwo\n").unwrap();
+    
+    // The blanks at the end of one file and the start of the next are a
+    // single run, squeezed to one line
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("-s").arg(file1.path()).arg(file2.path());
+    cmd.assert().success().stdout("one\n\ntwo\n");
+    
+    // Numbering carries on across the files and skips the squeezed lines
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("-sn").arg(file1.path()).arg(file2.path());
+    cmd.assert()
+        .success()
+        .stdout("     1\tone\n     2\t\n     3\ttwo\n");
+}
+
+#[test]
+fn test_cat_number_width_keeps_a_million_lines_aligned() {
+    let input = "x\n".repeat(1_000_000);
+    
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("-n").arg("--number-width=7").write_stdin(input);
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("      1\tx\n"));
+    assert!(stdout.ends_with("\n1000000\tx\n"));
+    assert!(stdout.lines().all(|line| line.find('\t') == Some(7)));
+}
+
+#[test]
+fn test_cat_number_separator() {
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("-nT")
+        .arg("--number-width=2")
+        .arg("--number-separator= | ")
+        .write_stdin("a\tb\nc\n");
+    cmd.assert().success().stdout(" 1 | a^Ib\n 2 | c\n");
+}
+
+#[test]
+fn test_cat_passes_binary_through_when_piped() {
+    let data = b"\x7fELF\x02\x01\x00\x00binary\n\xff\x00".to_vec();
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(&data).unwrap();
+    
+    // Output isn't a terminal here, so nothing is skipped
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg(file.path());
+    cmd.assert().success().stdout(data.clone()).stderr("");
+    
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("--binary").arg(file.path());
+    cmd.assert().success().stdout(data);
+}
+
+#[test]
+fn test_cat_dos2unix() {
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("--dos2unix").write_stdin("a\r\nb\r\n");
+    cmd.assert().success().stdout("a\nb\n");
+    
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("--dos2unix").arg("-nE").write_stdin("a\r\nb\r\n");
+    cmd.assert().success().stdout("     1\ta$\n     2\tb$\n");
+}
+
+#[test]
+fn test_cat_unix2dos() {
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("--unix2dos").write_stdin("a\r\nb\n");
+    cmd.assert().success().stdout("a\r\nb\r\n");
+    
+    // The $ goes after the added \r, which -A shows as ^M
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("--unix2dos").arg("-A").write_stdin("a\nb\n");
+    cmd.assert().success().stdout("a^M$\nb^M$\n");
+}
+
+#[test]
+fn test_cat_line_ending_flags_conflict() {
+    let mut cmd = Command::cargo_bin("cat").unwrap();
+    cmd.arg("--dos2unix").arg("--unix2dos");
+    cmd.assert().code(2);
+}
@@ -1,9 +1,17 @@
 use anyhow::Result;
+use clap::Parser;
+use crate::state::ShellState;
 use std::env;
 use std::fs;
-use std::io::{self, BufRead};
+use std::io::{self, BufRead, Read};
 use std::path::Path;
 
+/// Names the shell runs itself, offered when completing a command.
+pub const BUILTINS: [&str; 15] = [
+    "cat", "cd", "echo", "exit", "help", "ls", "mkdir", "mv", "pwd", "quit", "rm", "rmdir", "set",
+    "timeout", "touch",
+];
+
 pub fn help_command() -> Result<String> {
     let help_text = r#"
 Available Commands:
@@ -16,7 +24,7 @@ File Commands:
   mv <source> <dest>   - Move or rename files
 
 Directory Commands:
-  ls [-l] [-a] [path]  - List directory contents
+  ls [options] [path]  - List directory contents
   pwd                  - Print working directory
   cd <directory>       - Change directory
   mkdir [-p] <dir...>  - Create directories
@@ -24,13 +32,24 @@ Directory Commands:
 
 Utility Commands:
   echo <text...>       - Display text
+  timeout <secs> <cmd> - Run a command, stopping it after a deadline
+  set [-+]ex           - Exit scripts on error (-e), trace commands (-x)
+  set [-+]o <option>   - Turn a shell option on or off (noclobber)
   help                 - Show this help message
   exit                 - Exit the shell
 
 Special Syntax:
   >                    - Redirect output to file (overwrite)
   >>                   - Redirect output to file (append)
+  >|                   - Redirect output to file, even under noclobber
   |                    - Pipe output to another command
+  a ; b                - Run a, then b
+  a && b               - Run b only if a succeeds
+  a || b               - Run b only if a fails
+  $VAR, ${VAR}         - Value of an environment variable
+  'text'               - Literal text, spaces and all
+  "text"               - Text with spaces kept and variables expanded
+  \c                   - The character c, taken literally
 
 Examples:
   ls -l
@@ -69,57 +88,35 @@ pub fn cd_command(args: &[&str]) -> Result<String> {
     Ok(String::new())
 }
 
-pub fn ls_command(args: &[&str]) -> Result<String> {
-    let mut output = String::new();
-    
-    let show_all = args.contains(&"-a");
-    let long_format = args.contains(&"-l");
+/// Runs the standalone `ls` implementation, so the built-in accepts the
+/// same flags and produces the same listing. `terminal` says whether the
+/// output will reach the terminal rather than a file or a pipe.
+pub fn ls_command(args: &[&str], terminal: bool, state: &ShellState) -> Result<String> {
+    let args = ls::Args::try_parse_from(std::iter::once("ls").chain(args.iter().copied()))?
+        .terminal(terminal)
+        .deadline(state.deadline);
     
-    let path = args.iter()
-        .find(|&&arg| !arg.starts_with('-'))
-        .map(|&s| s)
-        .unwrap_or(".");
-    
-    let entries = fs::read_dir(path)?;
-    let mut files: Vec<_> = entries.collect::<Result<_, _>>()?;
-    files.sort_by_key(|e| e.file_name());
-    
-    for entry in files {
-        let file_name = entry.file_name();
-        let name = file_name.to_string_lossy();
-        
-        if !show_all && name.starts_with('.') {
-            continue;
-        }
-        
-        if long_format {
-            let metadata = entry.metadata()?;
-            let size = metadata.len();
-            let file_type = if metadata.is_dir() { "d" } else { "-" };
-            output.push_str(&format!("{} {:>10} {}\n", file_type, size, name));
-        } else {
-            output.push_str(&format!("{}\n", name));
-        }
-    }
+    let mut output = Vec::new();
+    ls::run(&args, &mut output)?;
     
-    Ok(output)
+    Ok(String::from_utf8_lossy(&output).into_owned())
 }
 
-pub fn cat_command(args: &[&str]) -> Result<String> {
+pub fn cat_command(args: &[&str], state: &ShellState) -> Result<String> {
     let mut output = String::new();
     
     if args.is_empty() {
         // Read from stdin
         let stdin = io::stdin();
         for line in stdin.lock().lines() {
+            state.check_deadline()?;
             output.push_str(&line?);
             output.push('\n');
         }
     } else {
         for file_name in args {
             if !file_name.starts_with('-') {
-                let content = fs::read_to_string(file_name)?;
-                output.push_str(&content);
+                output.push_str(&read_file(file_name, state)?);
             }
         }
     }
@@ -127,6 +124,25 @@ pub fn cat_command(args: &[&str]) -> Result<String> {
     Ok(output)
 }
 
+/// Reads a whole file as text a block at a time, so that `timeout` can
+/// stop a read that never ends.
+fn read_file(file_name: &str, state: &ShellState) -> Result<String> {
+    let mut file = fs::File::open(file_name)?;
+    let mut content = Vec::new();
+    let mut block = [0; 64 * 1024];
+    
+    loop {
+        state.check_deadline()?;
+        let read = file.read(&mut block)?;
+        if read == 0 {
+            break;
+        }
+        content.extend_from_slice(&block[..read]);
+    }
+    
+    Ok(String::from_utf8(content)?)
+}
+
 pub fn echo_command(args: &[&str]) -> Result<String> {
     let text = args.join(" ");
     Ok(format!("{}\n", text))
@@ -160,17 +176,15 @@ pub fn rmdir_command(args: &[&str]) -> Result<String> {
 
 pub fn touch_command(args: &[&str]) -> Result<String> {
     for arg in args {
-        if !arg.starts_with('-') {
-            if !Path::new(arg).exists() {
-                fs::File::create(arg)?;
-            }
+        if !arg.starts_with('-') && !Path::new(arg).exists() {
+            fs::File::create(arg)?;
         }
     }
     
     Ok(String::new())
 }
 
-pub fn rm_command(args: &[&str]) -> Result<String> {
+pub fn rm_command(args: &[&str], state: &ShellState) -> Result<String> {
     let recursive = args.contains(&"-r") || args.contains(&"-R");
     
     for arg in args {
@@ -178,7 +192,7 @@ pub fn rm_command(args: &[&str]) -> Result<String> {
             let path = Path::new(arg);
             if path.is_dir() {
                 if recursive {
-                    fs::remove_dir_all(path)?;
+                    remove_tree(path, state)?;
                 } else {
                     anyhow::bail!("rm: {}: is a directory", arg);
                 }
@@ -191,6 +205,23 @@ pub fn rm_command(args: &[&str]) -> Result<String> {
     Ok(String::new())
 }
 
+/// Removes `path` and everything under it, checking the `timeout`
+/// deadline before each entry. Symlinks are removed, never followed.
+fn remove_tree(path: &Path, state: &ShellState) -> Result<()> {
+    for entry in fs::read_dir(path)? {
+        state.check_deadline()?;
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            remove_tree(&entry.path(), state)?;
+        } else {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    
+    fs::remove_dir(path)?;
+    Ok(())
+}
+
 pub fn mv_command(args: &[&str]) -> Result<String> {
     if args.len() < 2 {
         anyhow::bail!("mv: missing destination file operand");
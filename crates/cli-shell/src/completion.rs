@@ -0,0 +1,183 @@
+//! Tab completion for the line editor: built-in command names where a
+//! command is expected, and file system paths everywhere else.
+
+use crate::commands::BUILTINS;
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+use std::fs;
+
+/// The editor helper, which only does completion.
+pub struct ShellHelper;
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        Ok(complete_line(line, pos))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+/// Completes the word that ends at `pos` in `line`, returning where the
+/// word starts and what could replace it.
+fn complete_line(line: &str, pos: usize) -> (usize, Vec<Pair>) {
+    let before = &line[..pos];
+    let word_length: usize = before
+        .chars()
+        .rev()
+        .take_while(|c| !c.is_whitespace())
+        .map(char::len_utf8)
+        .sum();
+    let start = pos - word_length;
+    let word = &before[start..];
+
+    // A command comes first on the line, or straight after a pipe or a
+    // `;`, `&&`, or `||`
+    let preceding = before[..start].trim_end();
+    let candidates = if preceding.is_empty() || preceding.ends_with(['|', ';', '&']) {
+        complete_command(word)
+    } else {
+        complete_path(word)
+    };
+
+    (start, candidates)
+}
+
+fn complete_command(prefix: &str) -> Vec<Pair> {
+    BUILTINS
+        .iter()
+        .filter(|name| name.starts_with(prefix))
+        .map(|name| Pair {
+            display: name.to_string(),
+            replacement: name.to_string(),
+        })
+        .collect()
+}
+
+/// Completes the last component of `word` against the entries of the
+/// directory before it, or of the current directory if there is none.
+/// Directories get a trailing `/` so completion can carry on inside them.
+fn complete_path(word: &str) -> Vec<Pair> {
+    let (dir, prefix) = match word.rfind('/') {
+        Some(slash) => word.split_at(slash + 1),
+        None => ("", word),
+    };
+
+    let Ok(entries) = fs::read_dir(if dir.is_empty() { "." } else { dir }) else {
+        return Vec::new();
+    };
+
+    let mut candidates: Vec<Pair> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            // Hidden files only when asked for, as with globs
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            // Follows symlinks, so a link to a directory counts as one
+            let suffix = if entry.path().is_dir() { "/" } else { "" };
+            Some(Pair {
+                display: format!("{}{}", name, suffix),
+                replacement: format!("{}{}{}", dir, name, suffix),
+            })
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| a.replacement.cmp(&b.replacement));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn replacements(candidates: &[Pair]) -> Vec<&str> {
+        candidates
+            .iter()
+            .map(|pair| pair.replacement.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_completes_commands_at_line_start() {
+        let (start, candidates) = complete_line("  mk", 4);
+        assert_eq!(start, 2);
+        assert_eq!(replacements(&candidates), ["mkdir"]);
+
+        let (_, candidates) = complete_line("r", 1);
+        assert_eq!(replacements(&candidates), ["rm", "rmdir"]);
+    }
+
+    #[test]
+    fn test_completes_commands_after_operators() {
+        let (start, candidates) = complete_line("ls | ca", 7);
+        assert_eq!(start, 5);
+        assert_eq!(replacements(&candidates), ["cat"]);
+
+        let (_, candidates) = complete_line("cd x && ec", 10);
+        assert_eq!(replacements(&candidates), ["echo"]);
+    }
+
+    #[test]
+    fn test_completes_paths_within_partial_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("src");
+        fs::create_dir_all(src.join("macros")).unwrap();
+        fs::write(src.join("main.rs"), "").unwrap();
+        fs::write(src.join("lib.rs"), "").unwrap();
+        fs::write(src.join(".mailmap"), "").unwrap();
+
+        let line = format!("cat {}/src/ma", temp_dir.path().display());
+        let (start, candidates) = complete_line(&line, line.len());
+        assert_eq!(start, 4);
+
+        let root = temp_dir.path().display();
+        assert_eq!(
+            replacements(&candidates),
+            [
+                format!("{}/src/macros/", root),
+                format!("{}/src/main.rs", root)
+            ]
+        );
+        let displays: Vec<&str> = candidates
+            .iter()
+            .map(|pair| pair.display.as_str())
+            .collect();
+        assert_eq!(displays, ["macros/", "main.rs"]);
+    }
+
+    #[test]
+    fn test_completes_hidden_files_only_when_asked() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join(".hidden"), "").unwrap();
+        fs::write(temp_dir.path().join("shown"), "").unwrap();
+
+        let dir = format!("{}/", temp_dir.path().display());
+        assert_eq!(complete_path(&dir).len(), 1);
+        assert_eq!(complete_path(&format!("{}.", dir)).len(), 1);
+    }
+
+    #[test]
+    fn test_missing_directory_completes_nothing() {
+        assert!(complete_path("/nonexistent_dir_12345/fi").is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+//! Where the shell's command lines come from: a line editor with history
+//! when it is interactive, or plain stdin when it runs a script.
+
+use crate::completion::ShellHelper;
+use anyhow::Result;
+use rustyline::config::{CompletionType, Config};
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::Editor;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+
+/// Name of the history file, kept in the home directory.
+const HISTORY_FILE: &str = ".rustcli_history";
+
+pub struct Input {
+    /// Only there for an interactive shell
+    editor: Option<Editor<ShellHelper, DefaultHistory>>,
+    /// Where history is loaded from and saved to
+    history: Option<PathBuf>,
+}
+
+impl Input {
+    /// Reads a script from stdin, with no prompt and no history.
+    pub fn script() -> Self {
+        Input {
+            editor: None,
+            history: None,
+        }
+    }
+
+    /// Reads from a line editor, so earlier commands can be recalled with
+    /// the up and down arrows and completed with Tab, starting with the
+    /// history saved in `history`, if any.
+    pub fn interactive(history: Option<PathBuf>) -> Result<Self> {
+        // List the candidates when there are several, as bash does
+        let config = Config::builder()
+            .history_ignore_dups(true)?
+            .completion_type(CompletionType::List)
+            .build();
+        let mut editor = Editor::with_config(config)?;
+        editor.set_helper(Some(ShellHelper));
+
+        if let Some(path) = &history {
+            match editor.load_history(path) {
+                Ok(()) => {}
+                // There is no history before the first session
+                Err(ReadlineError::Io(e)) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => eprintln!("cli-shell: cannot read history: {}", e),
+            }
+        }
+
+        Ok(Input {
+            editor: Some(editor),
+            history,
+        })
+    }
+
+    /// The history file in the home directory, if there is one.
+    pub fn history_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(HISTORY_FILE))
+    }
+
+    /// Reads the next line, showing `prompt` if interactive. Returns
+    /// `None` at the end of the input.
+    pub fn read_line(&mut self, prompt: &str) -> Result<Option<String>> {
+        let Some(editor) = &mut self.editor else {
+            let mut line = String::new();
+            return Ok((io::stdin().read_line(&mut line)? > 0).then_some(line));
+        };
+
+        match editor.readline(prompt) {
+            Ok(line) => Ok(Some(line)),
+            // Ctrl-C abandons the line being typed, as in other shells
+            Err(ReadlineError::Interrupted) => Ok(Some(String::new())),
+            Err(ReadlineError::Eof) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Adds `line` to the history, unless it is blank or the same as the
+    /// line before it.
+    pub fn remember(&mut self, line: &str) -> Result<()> {
+        if let Some(editor) = &mut self.editor {
+            editor.add_history_entry(line)?;
+        }
+        Ok(())
+    }
+
+    /// Appends the lines entered this session to the history file, so
+    /// shells running side by side don't overwrite each other's history.
+    pub fn save(&mut self) -> Result<()> {
+        if let (Some(editor), Some(path)) = (&mut self.editor, &self.history) {
+            editor.append_history(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entries(input: &Input) -> Vec<&String> {
+        input.editor.as_ref().unwrap().history().iter().collect()
+    }
+
+    #[test]
+    fn test_history_skips_blank_and_repeated_lines() {
+        let mut input = Input::interactive(None).unwrap();
+        for line in ["ls", "ls", "", "pwd", "ls"] {
+            input.remember(line).unwrap();
+        }
+        assert_eq!(entries(&input), ["ls", "pwd", "ls"]);
+    }
+
+    #[test]
+    fn test_history_persists_across_sessions() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(HISTORY_FILE);
+
+        let mut first = Input::interactive(Some(path.clone())).unwrap();
+        first.remember("echo one").unwrap();
+        first.save().unwrap();
+
+        let mut second = Input::interactive(Some(path.clone())).unwrap();
+        assert_eq!(entries(&second), ["echo one"]);
+        second.remember("echo two").unwrap();
+        second.save().unwrap();
+
+        let third = Input::interactive(Some(path)).unwrap();
+        assert_eq!(entries(&third), ["echo one", "echo two"]);
+    }
+
+    #[test]
+    fn test_script_input_keeps_no_history() {
+        let mut input = Input::script();
+        input.remember("ls").unwrap();
+        input.save().unwrap();
+        assert!(input.editor.is_none());
+    }
+}
@@ -1,27 +1,56 @@
 use anyhow::Result;
 use std::env;
 use std::fs::{File, OpenOptions};
-use std::io::{self, BufRead, Write};
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::ops::ControlFlow;
 use std::path::Path;
-use std::process::{Command, Stdio};
+use std::process::ExitCode;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
 
 mod commands;
+mod completion;
+mod input;
+mod state;
+mod tokenizer;
 use commands::*;
+use input::Input;
+use state::{set_command, ShellState};
+use tokenizer::{tokenize, unquoted};
 
-fn main() -> Result<()> {
-    println!("Rust CLI Shell v0.1.0");
-    println!("A recreation of the Java CLI-Custom project");
-    println!("Type 'help' for available commands, 'exit' to quit\n");
+fn main() -> ExitCode {
+    common::exit::report("cli-shell", run())
+}
+
+fn run() -> Result<()> {
+    // Commands piped in or redirected from a file run as a script
+    let interactive = io::stdin().is_terminal();
+    
+    if interactive {
+        println!("Rust CLI Shell v0.1.0");
+        println!("A recreation of the Java CLI-Custom project");
+        println!("Type 'help' for available commands, 'exit' to quit\n");
+    }
+    
+    let mut state = ShellState::default();
+    let mut lines = if interactive {
+        Input::interactive(Input::history_path())?
+    } else {
+        Input::script()
+    };
     
     loop {
-        // Print prompt
-        let current_dir = env::current_dir()?;
-        print!("{}> ", current_dir.display());
-        io::stdout().flush()?;
+        let prompt = if interactive {
+            format!("{}> ", env::current_dir()?.display())
+        } else {
+            String::new()
+        };
         
-        // Read input
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        // Read input, stopping at end of file
+        let Some(input) = lines.read_line(&prompt)? else {
+            break;
+        };
         
         let input = input.trim();
         
@@ -29,37 +58,180 @@ fn main() -> Result<()> {
             continue;
         }
         
-        // Check for exit command
-        if input == "exit" || input == "quit" {
+        lines.remember(input)?;
+        
+        if process_line(input, &mut state, interactive)?.is_break() {
             println!("Goodbye!");
             break;
         }
+    }
+    
+    // Losing the history is no reason to fail the whole session
+    if let Err(e) = lines.save() {
+        eprintln!("cli-shell: cannot save history: {}", e);
+    }
+    
+    Ok(())
+}
+
+/// How a command in a list is joined to the one before it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Joiner {
+    /// `;`, or the start of the line: always run
+    Then,
+    /// `&&`: run only if the last command that ran succeeded
+    And,
+    /// `||`: run only if the last command that ran failed
+    Or,
+}
+
+impl Joiner {
+    fn token(self) -> &'static str {
+        match self {
+            Joiner::Then => ";",
+            Joiner::And => "&&",
+            Joiner::Or => "||",
+        }
+    }
+}
+
+/// Runs a line of commands joined by `;`, `&&`, and `||`, printing each
+/// one's errors as it goes. Breaks if the line asks the shell to exit.
+///
+/// Only fails under `set -e` in a script, when a command fails that isn't
+/// followed by `&&` or `||`, since those handle the failure themselves.
+fn process_line(input: &str, state: &mut ShellState, interactive: bool) -> Result<ControlFlow<()>> {
+    let commands = split_list(input);
+    if let Err(e) = check_list(&commands) {
+        report_failure(e, state, interactive)?;
+        return Ok(ControlFlow::Continue(()));
+    }
+    
+    let mut succeeded = true;
+    for (i, &(joiner, command)) in commands.iter().enumerate() {
+        let skip = match joiner {
+            Joiner::Then => command.is_empty(),
+            Joiner::And => !succeeded,
+            Joiner::Or => succeeded,
+        };
+        if skip {
+            continue;
+        }
+        
+        if command == "exit" || command == "quit" {
+            return Ok(ControlFlow::Break(()));
+        }
+        
+        if state.options.xtrace {
+            eprintln!("+ {}", command);
+        }
         
-        // Process command
-        match process_command(input) {
-            Ok(_) => {}
-            Err(e) => eprintln!("Error: {}", e),
+        match process_command(command, state) {
+            Ok(()) => succeeded = true,
+            Err(e) => {
+                succeeded = false;
+                if matches!(commands.get(i + 1), Some((Joiner::And | Joiner::Or, _))) {
+                    eprintln!("Error: {}", e);
+                } else {
+                    report_failure(e, state, interactive)?;
+                }
+            }
         }
     }
     
+    Ok(ControlFlow::Continue(()))
+}
+
+/// Prints a command's error, unless it has to end the shell instead.
+fn report_failure(error: anyhow::Error, state: &ShellState, interactive: bool) -> Result<()> {
+    // Under `set -e` a script stops at the first failing command
+    if state.options.errexit && !interactive {
+        return Err(error);
+    }
+    eprintln!("Error: {}", error);
     Ok(())
 }
 
-fn process_command(input: &str) -> Result<()> {
+/// Splits `input` at each `;`, `&&`, and `||` outside quotes, pairing
+/// every command with the operator before it.
+fn split_list(input: &str) -> Vec<(Joiner, &str)> {
+    let mut commands = Vec::new();
+    let mut joiner = Joiner::Then;
+    let mut start = 0;
+    let mut chars = unquoted(input).into_iter().peekable();
+    
+    while let Some((i, ch)) = chars.next() {
+        let mut doubled = |c| chars.next_if(|&next| next == (i + 1, c)).is_some();
+        let next = match ch {
+            ';' => Joiner::Then,
+            '&' if doubled('&') => Joiner::And,
+            '|' if doubled('|') => Joiner::Or,
+            _ => continue,
+        };
+        
+        commands.push((joiner, input[start..i].trim()));
+        joiner = next;
+        start = i + next.token().len();
+    }
+    
+    commands.push((joiner, input[start..].trim()));
+    commands
+}
+
+/// Rejects a list with an operator that has no command on one side. Only
+/// a trailing `;` may be left without one.
+fn check_list(commands: &[(Joiner, &str)]) -> Result<()> {
+    for (i, &(joiner, command)) in commands.iter().enumerate() {
+        if !command.is_empty() {
+            continue;
+        }
+        let token = match (joiner, commands.get(i + 1)) {
+            (Joiner::Then, None) if i > 0 => continue,
+            (Joiner::Then, Some(&(next, _))) => next.token(),
+            (joiner, _) => joiner.token(),
+        };
+        anyhow::bail!("syntax error near unexpected token '{}'", token);
+    }
+    Ok(())
+}
+
+/// How a redirection opens its target file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RedirectMode {
+    /// `>`: truncate, unless noclobber is set and the file exists
+    Overwrite,
+    /// `>|`: truncate even under noclobber
+    Clobber,
+    /// `>>`: append
+    Append,
+}
+
+fn process_command(input: &str, state: &mut ShellState) -> Result<()> {
     // Check for piping first
-    if input.contains('|') {
-        return process_pipe(input);
+    if find_pipe(input).is_some() {
+        return process_pipe(input, state);
     }
     
     // Check for redirection
     let (cmd, redirect) = parse_redirection(input);
+    let redirect = redirect
+        .map(|(target, mode)| redirect_target(&target).map(|file| (file, mode)))
+        .transpose()?;
+    
+    // Refuse before running anything, so the command has no side effects
+    if let Some((file, RedirectMode::Overwrite)) = &redirect {
+        if state.options.noclobber && is_existing_file(file) {
+            anyhow::bail!("{}: cannot overwrite existing file", file);
+        }
+    }
     
     // Execute command and capture output if needed
-    let output = execute_single_command(&cmd)?;
+    let terminal = redirect.is_none() && io::stdout().is_terminal();
+    let output = execute_single_command(&cmd, state, terminal)?;
     
     // Handle redirection
-    if let Some((file, append)) = redirect {
-        write_to_file(&output, &file, append)?;
+    if let Some((file, mode)) = redirect {
+        write_to_file(&output, &file, mode == RedirectMode::Append)?;
     } else {
         print!("{}", output);
     }
@@ -67,22 +239,61 @@ fn process_command(input: &str) -> Result<()> {
     Ok(())
 }
 
-fn parse_redirection(input: &str) -> (String, Option<(String, bool)>) {
-    if let Some(pos) = input.find(">>") {
-        let (cmd, rest) = input.split_at(pos);
-        let file = rest[2..].trim().to_string();
-        (cmd.trim().to_string(), Some((file, true)))
-    } else if let Some(pos) = input.find('>') {
-        let (cmd, rest) = input.split_at(pos);
-        let file = rest[1..].trim().to_string();
-        (cmd.trim().to_string(), Some((file, false)))
+fn parse_redirection(input: &str) -> (String, Option<(String, RedirectMode)>) {
+    let Some(&(pos, _)) = unquoted(input).iter().find(|&&(_, c)| c == '>') else {
+        return (input.to_string(), None);
+    };
+    
+    let (cmd, rest) = input.split_at(pos);
+    let (mode, file) = if let Some(file) = rest.strip_prefix(">>") {
+        (RedirectMode::Append, file)
+    } else if let Some(file) = rest.strip_prefix(">|") {
+        (RedirectMode::Clobber, file)
     } else {
-        (input.to_string(), None)
+        (RedirectMode::Overwrite, &rest[1..])
+    };
+    (cmd.trim().to_string(), Some((file.trim().to_string(), mode)))
+}
+
+/// The file a redirection names, with its quotes removed. It must be a
+/// single word.
+fn redirect_target(target: &str) -> Result<String> {
+    match <[String; 1]>::try_from(tokenize(target)?) {
+        Ok([file]) => Ok(file),
+        Err(words) if words.is_empty() => {
+            anyhow::bail!("syntax error near unexpected token 'newline'")
+        }
+        Err(_) => anyhow::bail!("{}: ambiguous redirect", target),
     }
 }
 
+/// Finds the first `|` that separates commands, skipping the one in `>|`.
+fn find_pipe(input: &str) -> Option<usize> {
+    unquoted(input)
+        .into_iter()
+        .filter(|&(_, c)| c == '|')
+        .map(|(pos, _)| pos)
+        .find(|&pos| !input[..pos].ends_with('>'))
+}
+
+fn split_pipeline(input: &str) -> Vec<&str> {
+    let mut commands = Vec::new();
+    let mut rest = input;
+    while let Some(pos) = find_pipe(rest) {
+        commands.push(rest[..pos].trim());
+        rest = &rest[pos + 1..];
+    }
+    commands.push(rest.trim());
+    commands
+}
+
+/// Noclobber only protects regular files; `> /dev/null` keeps working.
+fn is_existing_file(filename: &str) -> bool {
+    Path::new(filename).metadata().is_ok_and(|m| m.is_file())
+}
+
 fn write_to_file(content: &str, filename: &str, append: bool) -> Result<()> {
-    let mut file = if append {
+    let file = if append {
         OpenOptions::new()
             .create(true)
             .append(true)
@@ -91,63 +302,353 @@ fn write_to_file(content: &str, filename: &str, append: bool) -> Result<()> {
         File::create(filename)?
     };
     
-    file.write_all(content.as_bytes())?;
+    let mut writer = BufWriter::new(file);
+    writer.write_all(content.as_bytes())?;
+    writer.flush()?;
+    
+    // Make sure the data has reached the disk before the next command runs
+    writer.get_ref().sync_all()?;
     Ok(())
 }
 
-fn process_pipe(input: &str) -> Result<()> {
-    let commands: Vec<&str> = input.split('|').map(|s| s.trim()).collect();
+fn process_pipe(input: &str, state: &mut ShellState) -> Result<()> {
+    let commands = split_pipeline(input);
     
     if commands.len() < 2 {
         anyhow::bail!("Invalid pipe syntax");
     }
     
-    let mut output = execute_single_command(commands[0])?;
+    let mut output = execute_single_command(commands[0], state, false)?;
     
-    for cmd in &commands[1..] {
-        output = execute_with_input(cmd, &output)?;
+    // Only the last command's output reaches the terminal
+    for (i, cmd) in commands.iter().enumerate().skip(1) {
+        let terminal = i == commands.len() - 1 && io::stdout().is_terminal();
+        output = execute_with_input(cmd, &output, state, terminal)?;
     }
     
     print!("{}", output);
     Ok(())
 }
 
-fn execute_with_input(cmd: &str, input: &str) -> Result<String> {
-    let parts: Vec<&str> = cmd.split_whitespace().collect();
-    if parts.is_empty() {
-        anyhow::bail!("Empty command");
-    }
+fn execute_with_input(
+    cmd: &str,
+    input: &str,
+    state: &mut ShellState,
+    terminal: bool,
+) -> Result<String> {
+    let words = tokenize(cmd)?;
     
     // For built-in commands that accept input
-    match parts[0] {
-        "cat" if parts.len() == 1 => Ok(input.to_string()),
-        _ => execute_single_command(cmd),
+    match words.as_slice() {
+        [] => anyhow::bail!("Empty command"),
+        [command] if command == "cat" => Ok(input.to_string()),
+        _ => execute_words(&words, state, terminal),
     }
 }
 
-fn execute_single_command(input: &str) -> Result<String> {
-    let parts: Vec<&str> = input.split_whitespace().collect();
-    
-    if parts.is_empty() {
+fn execute_single_command(input: &str, state: &mut ShellState, terminal: bool) -> Result<String> {
+    execute_words(&tokenize(input)?, state, terminal)
+}
+
+/// Runs the built-in the first word names, with the rest as arguments.
+/// `terminal` says whether its output will be printed on a terminal.
+fn execute_words(words: &[String], state: &mut ShellState, terminal: bool) -> Result<String> {
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let [command, args @ ..] = words.as_slice() else {
         return Ok(String::new());
-    }
-    
-    let command = parts[0];
-    let args = &parts[1..];
+    };
+    state.check_deadline()?;
     
-    match command {
+    match *command {
         "help" => help_command(),
         "pwd" => pwd_command(),
         "cd" => cd_command(args),
-        "ls" => ls_command(args),
-        "cat" => cat_command(args),
+        "ls" => ls_command(args, terminal, state),
+        "cat" => cat_command(args, state),
         "echo" => echo_command(args),
         "mkdir" => mkdir_command(args),
         "rmdir" => rmdir_command(args),
         "touch" => touch_command(args),
-        "rm" => rm_command(args),
+        "rm" => rm_command(args, state),
         "mv" => mv_command(args),
+        "set" => set_command(args, &mut state.options),
+        "timeout" => timeout_command(args, state, terminal),
         _ => Err(anyhow::anyhow!("Command not found: {}", command)),
     }
 }
 
+/// Runs a command on a worker thread, stopping it once the deadline passes.
+///
+/// Built-ins run inside the shell process and cannot be killed, so the
+/// command gets the deadline in its copy of the shell state instead. The
+/// long-running loops (the `rm -r` walk, `ls -R`, and `cat`'s reads) check
+/// it and stop at the first check after it passes; a command stuck in a
+/// single system call, such as opening a FIFO with no writer, stops once
+/// that call returns. Its output is discarded either way. The copy of the
+/// state also means a `set` under `timeout` doesn't outlive it.
+fn timeout_command(args: &[&str], state: &ShellState, terminal: bool) -> Result<String> {
+    let [duration, command @ ..] = args else {
+        anyhow::bail!("usage: timeout SECONDS COMMAND [ARGS...]");
+    };
+    if command.is_empty() {
+        anyhow::bail!("usage: timeout SECONDS COMMAND [ARGS...]");
+    }
+    
+    let seconds: f64 = duration
+        .parse()
+        .ok()
+        .filter(|s: &f64| s.is_finite() && *s >= 0.0)
+        .ok_or_else(|| anyhow::anyhow!("timeout: invalid time interval '{}'", duration))?;
+    
+    let command: Vec<String> = command.iter().map(|word| word.to_string()).collect();
+    
+    // As with GNU timeout, a duration of 0 disables the deadline
+    let mut state = state.clone();
+    if seconds == 0.0 {
+        return execute_words(&command, &mut state, terminal);
+    }
+    
+    // An outer timeout's deadline still applies if it comes first
+    let limit = Duration::from_secs_f64(seconds);
+    let deadline = Instant::now() + limit;
+    state.deadline = Some(state.deadline.map_or(deadline, |outer| outer.min(deadline)));
+    
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        // The receiver is gone if we already timed out
+        let _ = sender.send(execute_words(&command, &mut state, terminal));
+    });
+    
+    match receiver.recv_timeout(limit) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            anyhow::bail!("timeout: command timed out after {} seconds", duration)
+        }
+        Err(RecvTimeoutError::Disconnected) => anyhow::bail!("timeout: command panicked"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_to_file_large_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("large.txt");
+        let content: String = (0..100_000).map(|i| format!("line {}\n", i)).collect();
+        
+        write_to_file(&content, path.to_str().unwrap(), false).unwrap();
+        
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    }
+
+    #[test]
+    fn test_redirect_large_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let input = temp_dir.path().join("input.txt");
+        let output = temp_dir.path().join("output.txt");
+        let content: String = (0..100_000).map(|i| format!("line {}\n", i)).collect();
+        fs::write(&input, &content).unwrap();
+        
+        process_command(&format!("cat {} > {}", input.display(), output.display()), &mut ShellState::default()).unwrap();
+        
+        assert_eq!(fs::read_to_string(&output).unwrap(), content);
+    }
+
+    #[test]
+    fn test_redirect_append() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("output.txt");
+        
+        process_command(&format!("echo first > {}", output.display()), &mut ShellState::default()).unwrap();
+        process_command(&format!("echo second >> {}", output.display()), &mut ShellState::default()).unwrap();
+        
+        assert_eq!(fs::read_to_string(&output).unwrap(), "first\nsecond\n");
+    }
+
+    #[test]
+    fn test_noclobber_protects_existing_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("output.txt");
+        let mut state = ShellState::default();
+        
+        process_command("set -o noclobber", &mut state).unwrap();
+        process_command(&format!("echo first > {}", output.display()), &mut state).unwrap();
+        
+        let error = process_command(&format!("echo second > {}", output.display()), &mut state)
+            .unwrap_err();
+        assert!(error.to_string().contains("cannot overwrite existing file"));
+        assert_eq!(fs::read_to_string(&output).unwrap(), "first\n");
+        
+        process_command(&format!("echo third >| {}", output.display()), &mut state).unwrap();
+        assert_eq!(fs::read_to_string(&output).unwrap(), "third\n");
+        
+        process_command("set +o noclobber", &mut state).unwrap();
+        process_command(&format!("echo fourth > {}", output.display()), &mut state).unwrap();
+        assert_eq!(fs::read_to_string(&output).unwrap(), "fourth\n");
+    }
+
+    #[test]
+    fn test_split_list() {
+        assert_eq!(
+            split_list("a ; b && c || d | e"),
+            [
+                (Joiner::Then, "a"),
+                (Joiner::Then, "b"),
+                (Joiner::And, "c"),
+                (Joiner::Or, "d | e"),
+            ]
+        );
+        assert_eq!(split_list("echo hi >| out"), [(Joiner::Then, "echo hi >| out")]);
+        // Quoted and escaped operators are just text
+        let quoted = "echo 'a;b' \"c&&d\" e\\;f";
+        assert_eq!(split_list(quoted), [(Joiner::Then, quoted)]);
+    }
+
+    #[test]
+    fn test_check_list_rejects_missing_commands() {
+        assert!(check_list(&split_list("a ;")).is_ok());
+        assert!(check_list(&split_list("a;b")).is_ok());
+        let error = check_list(&split_list("a &&")).unwrap_err();
+        assert_eq!(error.to_string(), "syntax error near unexpected token '&&'");
+        assert!(check_list(&split_list("; a")).is_err());
+        assert!(check_list(&split_list("a ;; b")).is_err());
+        assert!(check_list(&split_list("|| a")).is_err());
+    }
+
+    #[test]
+    fn test_redirect_target_must_be_one_word() {
+        assert_eq!(redirect_target("\"my file\"").unwrap(), "my file");
+        assert!(redirect_target("two words").is_err());
+        assert!(redirect_target("").is_err());
+    }
+
+    #[test]
+    fn test_quoted_operators_are_text() {
+        assert_eq!(find_pipe("echo 'a|b'"), None);
+        assert_eq!(parse_redirection("echo \"a > b\"").1, None);
+        assert_eq!(
+            parse_redirection("echo '>' >> out"),
+            ("echo '>'".to_string(), Some(("out".to_string(), RedirectMode::Append)))
+        );
+    }
+
+    #[test]
+    fn test_split_pipeline_skips_clobber_operator() {
+        assert_eq!(split_pipeline("ls | cat >| out"), ["ls", "cat >| out"]);
+        assert_eq!(find_pipe("echo hi >| out"), None);
+    }
+
+    #[test]
+    fn test_ls_sorts_by_time_like_standalone_ls() {
+        let temp_dir = TempDir::new().unwrap();
+        let now = std::time::SystemTime::now();
+        for (name, age) in [("old.txt", 300), ("newest.txt", 0), ("middle.txt", 100)] {
+            let file = fs::File::create(temp_dir.path().join(name)).unwrap();
+            file.set_modified(now - Duration::from_secs(age)).unwrap();
+        }
+        
+        let dir = temp_dir.path().display();
+        
+        let output = execute_single_command(&format!("ls -lat {}", dir), &mut ShellState::default(), false).unwrap();
+        let names: Vec<&str> = output
+            .lines()
+            .map(|line| line.rsplit(' ').next().unwrap())
+            .collect();
+        assert_eq!(names, ["newest.txt", "middle.txt", "old.txt"]);
+        
+        let reversed = execute_single_command(&format!("ls -tr {}", dir), &mut ShellState::default(), false).unwrap();
+        assert_eq!(reversed, "old.txt\nmiddle.txt\nnewest.txt\n");
+    }
+
+    #[test]
+    fn test_redirected_ls_lists_one_per_line() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["aa", "bb", "cc"] {
+            fs::File::create(temp_dir.path().join(name)).unwrap();
+        }
+        let output = temp_dir.path().join("out.txt");
+        
+        // The file is only created once ls has finished
+        process_command(&format!("ls {} > {}", temp_dir.path().display(), output.display()), &mut ShellState::default()).unwrap();
+        
+        assert_eq!(fs::read_to_string(&output).unwrap(), "aa\nbb\ncc\n");
+    }
+
+    #[test]
+    fn test_redirected_ls_has_no_auto_hyperlinks() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::File::create(temp_dir.path().join("aa")).unwrap();
+        let output = temp_dir.path().join("out.txt");
+        
+        process_command(
+            &format!("ls --hyperlink=auto {} > {}", temp_dir.path().display(), output.display()),
+            &mut ShellState::default(),
+        )
+        .unwrap();
+        
+        assert_eq!(fs::read_to_string(&output).unwrap(), "aa\n");
+    }
+
+    #[test]
+    fn test_ls_rejects_unknown_flags() {
+        assert!(execute_single_command("ls --no-such-flag", &mut ShellState::default(), false).is_err());
+    }
+
+    #[test]
+    fn test_timeout_passes_output_through() {
+        assert_eq!(timeout_command(&["5", "echo", "hi"], &ShellState::default(), false).unwrap(), "hi\n");
+        assert_eq!(timeout_command(&["0", "echo", "hi"], &ShellState::default(), false).unwrap(), "hi\n");
+    }
+
+    #[test]
+    fn test_timeout_usage_errors() {
+        assert!(timeout_command(&[], &ShellState::default(), false).is_err());
+        assert!(timeout_command(&["5"], &ShellState::default(), false).is_err());
+        assert!(timeout_command(&["soon", "echo"], &ShellState::default(), false).is_err());
+        assert!(timeout_command(&["-1", "echo"], &ShellState::default(), false).is_err());
+    }
+
+    #[test]
+    fn test_builtins_stop_once_the_deadline_passes() {
+        let temp_dir = TempDir::new().unwrap();
+        let tree = temp_dir.path().join("tree");
+        fs::create_dir_all(tree.join("sub")).unwrap();
+        fs::write(tree.join("sub/file.txt"), "data").unwrap();
+        let tree = tree.display().to_string();
+        
+        let mut state = ShellState {
+            deadline: Some(Instant::now()),
+            ..Default::default()
+        };
+        assert!(execute_words(&["echo".to_string()], &mut state, false).is_err());
+        
+        // Each built-in checks for itself, as it goes
+        assert!(rm_command(&["-r", &tree], &state).is_err());
+        assert!(ls_command(&["-R", &tree], false, &state).is_err());
+        let file = format!("{}/sub/file.txt", tree);
+        assert!(cat_command(&[&file], &state).is_err());
+        assert!(Path::new(&file).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout_expires_on_blocked_command() {
+        let temp_dir = TempDir::new().unwrap();
+        let fifo = temp_dir.path().join("fifo");
+        let status = std::process::Command::new("mkfifo").arg(&fifo).status().unwrap();
+        assert!(status.success());
+        
+        // Opening a FIFO with no writer blocks, so cat never returns
+        let start = std::time::Instant::now();
+        let error = timeout_command(&["0.2", "cat", fifo.to_str().unwrap()], &ShellState::default(), false).unwrap_err();
+        assert!(error.to_string().contains("timed out"));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        
+        // Release the worker thread
+        drop(OpenOptions::new().write(true).open(&fifo).unwrap());
+    }
+}
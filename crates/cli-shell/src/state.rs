@@ -0,0 +1,155 @@
+use anyhow::Result;
+use std::time::Instant;
+
+/// Everything the shell remembers between commands, apart from the
+/// working directory, which lives in the process itself.
+#[derive(Debug, Clone, Default)]
+pub struct ShellState {
+    pub options: ShellOptions,
+    /// When a command under `timeout` has to stop
+    pub deadline: Option<Instant>,
+}
+
+impl ShellState {
+    /// Fails once the deadline set by `timeout` has passed. Built-ins call
+    /// this as they go, since the shell can't kill its own threads.
+    pub fn check_deadline(&self) -> Result<()> {
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            anyhow::bail!("deadline passed");
+        }
+        Ok(())
+    }
+}
+
+/// Options toggled with the `set` built-in.
+#[derive(Debug, Clone, Default)]
+pub struct ShellOptions {
+    /// `>` refuses to overwrite existing files; `>|` still can
+    pub noclobber: bool,
+    /// A failing command ends a non-interactive shell
+    pub errexit: bool,
+    /// Each command is echoed to stderr, prefixed with `+`, before it runs
+    pub xtrace: bool,
+}
+
+impl ShellOptions {
+    /// Single-letter flags and the long names used by `set -o NAME`.
+    const FLAGS: [(char, &'static str); 3] =
+        [('e', "errexit"), ('C', "noclobber"), ('x', "xtrace")];
+
+    fn flag_mut(&mut self, name: &str) -> Option<&mut bool> {
+        match name {
+            "errexit" => Some(&mut self.errexit),
+            "noclobber" => Some(&mut self.noclobber),
+            "xtrace" => Some(&mut self.xtrace),
+            _ => None,
+        }
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        match name {
+            "errexit" => self.errexit,
+            "noclobber" => self.noclobber,
+            "xtrace" => self.xtrace,
+            _ => false,
+        }
+    }
+}
+
+/// `set -o NAME` turns an option on and `set +o NAME` turns it off, as do
+/// the single-letter forms `-e`, `-C`, and `-x`, which can be combined
+/// (`set -ex`). With no name, `-o` lists every option and whether it is
+/// on.
+pub fn set_command(args: &[&str], options: &mut ShellOptions) -> Result<String> {
+    let mut output = String::new();
+    let mut args = args.iter();
+
+    while let Some(&arg) = args.next() {
+        let (enable, letters) = match arg.split_at_checked(1) {
+            Some(("-", letters)) if !letters.is_empty() => (true, letters),
+            Some(("+", letters)) if !letters.is_empty() => (false, letters),
+            _ => anyhow::bail!("set: {}: invalid option", arg),
+        };
+
+        for letter in letters.chars() {
+            let name = if letter == 'o' {
+                match args.next() {
+                    Some(&name) => name,
+                    None => {
+                        for (_, name) in ShellOptions::FLAGS {
+                            let state = if options.flag(name) { "on" } else { "off" };
+                            output.push_str(&format!("{:<15}\t{}\n", name, state));
+                        }
+                        continue;
+                    }
+                }
+            } else {
+                ShellOptions::FLAGS
+                    .iter()
+                    .find(|(short, _)| *short == letter)
+                    .map(|(_, name)| *name)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("set: {}{}: invalid option", &arg[..1], letter)
+                    })?
+            };
+
+            let flag = options
+                .flag_mut(name)
+                .ok_or_else(|| anyhow::anyhow!("set: {}: invalid option name", name))?;
+            *flag = enable;
+        }
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_toggles_noclobber() {
+        let mut options = ShellOptions::default();
+
+        set_command(&["-o", "noclobber"], &mut options).unwrap();
+        assert!(options.noclobber);
+        set_command(&["+o", "noclobber"], &mut options).unwrap();
+        assert!(!options.noclobber);
+        set_command(&["-C"], &mut options).unwrap();
+        assert!(options.noclobber);
+    }
+
+    #[test]
+    fn test_set_short_flags_combine() {
+        let mut options = ShellOptions::default();
+
+        set_command(&["-ex"], &mut options).unwrap();
+        assert!(options.errexit && options.xtrace);
+        set_command(&["+x", "-o", "noclobber"], &mut options).unwrap();
+        assert!(options.errexit && !options.xtrace && options.noclobber);
+        set_command(&["+e"], &mut options).unwrap();
+        assert!(!options.errexit);
+    }
+
+    #[test]
+    fn test_set_lists_options() {
+        let mut options = ShellOptions {
+            noclobber: true,
+            ..Default::default()
+        };
+        let output = set_command(&["-o"], &mut options).unwrap();
+        assert_eq!(
+            output,
+            "errexit        \toff\nnoclobber      \ton\nxtrace         \toff\n"
+        );
+    }
+
+    #[test]
+    fn test_set_rejects_unknown_options() {
+        let mut options = ShellOptions::default();
+        assert!(set_command(&["-o", "nosuch"], &mut options).is_err());
+        assert!(set_command(&["-Q"], &mut options).is_err());
+        assert!(set_command(&["noclobber"], &mut options).is_err());
+        assert!(set_command(&["-"], &mut options).is_err());
+    }
+}
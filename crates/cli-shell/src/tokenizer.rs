@@ -0,0 +1,231 @@
+//! Splitting a command into words, with the quoting rules of a POSIX
+//! shell.
+
+use anyhow::Result;
+use std::env;
+use std::iter::Peekable;
+use std::mem;
+use std::str::Chars;
+
+/// Splits `input` into words at whitespace, removing quotes and
+/// backslashes and expanding variables as it goes.
+///
+/// Single quotes keep everything up to the next one as it is. Double
+/// quotes keep whitespace too, but a backslash inside them still escapes
+/// `$`, `"`, `\`, and `` ` ``. Elsewhere a backslash makes the next
+/// character an ordinary one. A pair of quotes with nothing between them
+/// is an empty word.
+///
+/// `$NAME` and `${NAME}` outside single quotes become the value of the
+/// environment variable. The value is always part of the word it appears
+/// in: it is never split or read for quotes or operators. An unset or
+/// empty variable outside quotes makes no word of its own. A `$` that
+/// doesn't start a valid name stays as it is.
+pub fn tokenize(input: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    // Set once the word has begun, which quotes do even if they are empty
+    let mut in_word = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(mem::take(&mut word));
+                    in_word = false;
+                }
+                continue;
+            }
+            '\'' => loop {
+                match chars.next() {
+                    Some('\'') => break,
+                    Some(c) => word.push(c),
+                    None => anyhow::bail!("unterminated single quote"),
+                }
+            },
+            '"' => loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('$') => expand_variable(&mut chars, &mut word),
+                    Some('\\') => match chars.next() {
+                        Some(c @ ('$' | '"' | '\\' | '`')) => word.push(c),
+                        Some(c) => {
+                            word.push('\\');
+                            word.push(c);
+                        }
+                        None => anyhow::bail!("unterminated double quote"),
+                    },
+                    Some(c) => word.push(c),
+                    None => anyhow::bail!("unterminated double quote"),
+                }
+            },
+            // A backslash at the very end has nothing to escape
+            '\\' => word.push(chars.next().unwrap_or('\\')),
+            '$' => {
+                let start = word.len();
+                expand_variable(&mut chars, &mut word);
+                if word.len() == start {
+                    continue;
+                }
+            }
+            c => word.push(c),
+        }
+        in_word = true;
+    }
+
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Appends the value of the variable named after a `$` to `word`, or the
+/// `$` itself if no name follows.
+fn expand_variable(chars: &mut Peekable<Chars>, word: &mut String) {
+    match take_variable_name(chars) {
+        Some(name) => {
+            if let Some(value) = env::var_os(&name) {
+                word.push_str(&value.to_string_lossy());
+            }
+        }
+        None => word.push('$'),
+    }
+}
+
+/// Takes the name after a `$`, either bare or in braces, leaving `chars`
+/// alone if there isn't one.
+fn take_variable_name(chars: &mut Peekable<Chars>) -> Option<String> {
+    let is_start = |c: &char| c.is_ascii_alphabetic() || *c == '_';
+    let is_rest = |c: &char| c.is_ascii_alphanumeric() || *c == '_';
+    
+    if chars.peek() == Some(&'{') {
+        let mut lookahead = chars.clone();
+        lookahead.next();
+        let name: String = lookahead.clone().take_while(|c| *c != '}').collect();
+        let valid = name.chars().next().is_some_and(|c| is_start(&c))
+            && name.chars().all(|c| is_rest(&c));
+        // Skip the name and the closing brace, which must be there
+        if !valid || lookahead.nth(name.len()) != Some('}') {
+            return None;
+        }
+        *chars = lookahead;
+        return Some(name);
+    }
+    
+    let first = chars.next_if(is_start)?;
+    let mut name = String::from(first);
+    while let Some(c) = chars.next_if(is_rest) {
+        name.push(c);
+    }
+    Some(name)
+}
+
+/// The characters of `input` outside quotes that aren't escaped, with
+/// their byte positions, for finding operators such as `|` and `>` that
+/// quoting turns into plain text. The quotes and backslashes are left out.
+pub fn unquoted(input: &str) -> Vec<(usize, char)> {
+    let mut found = Vec::new();
+    let mut quote = None;
+    let mut chars = input.char_indices();
+
+    while let Some((i, ch)) = chars.next() {
+        match (quote, ch) {
+            (Some(q), _) if ch == q => quote = None,
+            (None | Some('"'), '\\') => {
+                chars.next();
+            }
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(ch),
+            (None, _) => found.push((i, ch)),
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_splits_on_whitespace() {
+        assert_eq!(tokenize("  ls   -l\tdir ").unwrap(), ["ls", "-l", "dir"]);
+        assert!(tokenize("   ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_quotes_keep_spaces() {
+        assert_eq!(
+            tokenize("echo \"hello   world\"").unwrap(),
+            ["echo", "hello   world"]
+        );
+        assert_eq!(
+            tokenize("touch 'my file.txt'").unwrap(),
+            ["touch", "my file.txt"]
+        );
+        assert_eq!(tokenize("echo \"\" ''").unwrap(), ["echo", "", ""]);
+    }
+
+    #[test]
+    fn test_embedded_quotes() {
+        assert_eq!(tokenize("a\"b c\"d").unwrap(), ["ab cd"]);
+        assert_eq!(tokenize("\"it's\"").unwrap(), ["it's"]);
+        assert_eq!(tokenize("'say \"hi\"'").unwrap(), ["say \"hi\""]);
+        assert_eq!(tokenize("\"say \\\"hi\\\"\"").unwrap(), ["say \"hi\""]);
+    }
+
+    #[test]
+    fn test_escaped_characters() {
+        assert_eq!(tokenize("my\\ file").unwrap(), ["my file"]);
+        assert_eq!(tokenize("\\'quoted\\'").unwrap(), ["'quoted'"]);
+        assert_eq!(tokenize("a\\\\b").unwrap(), ["a\\b"]);
+        assert_eq!(tokenize("end\\").unwrap(), ["end\\"]);
+        // Inside double quotes only a few characters can be escaped
+        assert_eq!(tokenize("\"\\$HOME \\n\"").unwrap(), ["$HOME \\n"]);
+        // Inside single quotes nothing can
+        assert_eq!(tokenize("'a\\b'").unwrap(), ["a\\b"]);
+    }
+
+    #[test]
+    fn test_unterminated_quotes() {
+        let error = tokenize("echo 'oops").unwrap_err();
+        assert_eq!(error.to_string(), "unterminated single quote");
+        let error = tokenize("echo \"oops").unwrap_err();
+        assert_eq!(error.to_string(), "unterminated double quote");
+        assert!(tokenize("echo \"oops\\\"").is_err());
+    }
+
+    #[test]
+    fn test_expands_variables() {
+        let home = env::var("HOME").unwrap_or_default();
+        let path = env::var("PATH").unwrap_or_default();
+        assert_eq!(tokenize("echo $HOME").unwrap(), ["echo", home.as_str()]);
+        assert_eq!(tokenize("${PATH}:x").unwrap(), [format!("{}:x", path)]);
+        assert_eq!(tokenize("a $NOPE_RUSTCLI_UNSET b").unwrap(), ["a", "b"]);
+        assert_eq!(tokenize("a${NOPE_RUSTCLI_UNSET}b").unwrap(), ["ab"]);
+        assert_eq!(tokenize("\"$NOPE_RUSTCLI_UNSET\"").unwrap(), [""]);
+    }
+
+    #[test]
+    fn test_leaves_non_names_alone() {
+        assert_eq!(tokenize("cost $5 or $").unwrap(), ["cost", "$5", "or", "$"]);
+        assert_eq!(tokenize("${unclosed").unwrap(), ["${unclosed"]);
+        assert_eq!(tokenize("${}").unwrap(), ["${}"]);
+    }
+
+    #[test]
+    fn test_expansion_respects_quotes_and_escapes() {
+        let path = env::var("PATH").unwrap_or_default();
+        assert_eq!(tokenize("'$PATH'").unwrap(), ["$PATH"]);
+        assert_eq!(tokenize("\\$PATH").unwrap(), ["$PATH"]);
+        assert_eq!(tokenize("\"'$PATH'\"").unwrap(), [format!("'{}'", path)]);
+    }
+
+    #[test]
+    fn test_unquoted_skips_quoted_text() {
+        let chars: String = unquoted("a'|'\"|\"\\|b|").iter().map(|&(_, c)| c).collect();
+        assert_eq!(chars, "ab|");
+        assert_eq!(unquoted("x | y")[2], (2, '|'));
+    }
+}
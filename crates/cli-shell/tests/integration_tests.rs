@@ -0,0 +1,165 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn test_script_runs_to_end_of_input() {
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.write_stdin("echo one\necho two\n");
+    cmd.assert().success().stdout("one\ntwo\n");
+}
+
+#[test]
+fn test_set_x_traces_commands_to_stderr() {
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.write_stdin("echo before\nset -x\necho traced\nset +x\necho after\n");
+    cmd.assert()
+        .success()
+        .stdout("before\ntraced\nafter\n")
+        .stderr("+ echo traced\n+ set +x\n");
+}
+
+#[test]
+fn test_set_e_stops_script_at_failure() {
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.write_stdin("set -e\necho before\nno-such-command\necho after\n");
+    cmd.assert()
+        .code(1)
+        .stdout("before\n")
+        .stderr(predicate::str::contains("Command not found: no-such-command"));
+}
+
+#[test]
+fn test_script_continues_after_failure_without_set_e() {
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.write_stdin("no-such-command\necho after\n");
+    cmd.assert()
+        .success()
+        .stdout("after\n")
+        .stderr(predicate::str::contains("Command not found"));
+}
+
+#[test]
+fn test_variables_are_expanded() {
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.env("RUSTCLI_GREETING", "hello")
+        .env_remove("RUSTCLI_UNSET")
+        .write_stdin("echo $RUSTCLI_GREETING ${RUSTCLI_GREETING}world [$RUSTCLI_UNSET]\n");
+    cmd.assert().success().stdout("hello helloworld []\n");
+}
+
+#[test]
+fn test_variable_values_are_not_parsed_again() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .env("RUSTCLI_REDIRECT", "hi > injected.txt")
+        .env("RUSTCLI_PIPE", "a | b && c")
+        .env("RUSTCLI_QUOTE", "it's \"quoted\"")
+        .write_stdin("echo $RUSTCLI_REDIRECT\necho $RUSTCLI_PIPE\necho $RUSTCLI_QUOTE\n");
+    cmd.assert()
+        .success()
+        .stdout("hi > injected.txt\na | b && c\nit's \"quoted\"\n")
+        .stderr("");
+    
+    assert!(!temp_dir.path().join("injected.txt").exists());
+}
+
+#[test]
+fn test_and_runs_only_after_success() {
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.write_stdin("no-such-command && echo x\necho a && echo b\n");
+    cmd.assert()
+        .success()
+        .stdout("a\nb\n")
+        .stderr(predicate::str::contains("Command not found: no-such-command"));
+}
+
+#[test]
+fn test_or_runs_only_after_failure() {
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.write_stdin("no-such-command || echo y\necho a || echo b\n");
+    cmd.assert().success().stdout("y\na\n");
+}
+
+#[test]
+fn test_semicolon_runs_commands_in_order() {
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.write_stdin("echo a ; no-such-command ; echo b;\n");
+    cmd.assert().success().stdout("a\nb\n");
+}
+
+#[test]
+fn test_skipped_commands_keep_the_last_status() {
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.write_stdin("no-such-command && echo x || echo y\necho a || echo x && echo b\n");
+    cmd.assert().success().stdout("y\na\nb\n");
+}
+
+#[test]
+fn test_set_e_ignores_failures_handled_by_or() {
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.write_stdin("set -e\nno-such-command || echo handled\necho a; no-such-command; echo never\n");
+    cmd.assert().code(1).stdout("handled\na\n");
+}
+
+#[test]
+fn test_exit_in_a_list() {
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.write_stdin("echo a && exit\necho never\n");
+    cmd.assert().success().stdout("a\nGoodbye!\n");
+}
+
+#[test]
+fn test_list_syntax_error() {
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.write_stdin("echo a &&\necho b\n");
+    cmd.assert()
+        .success()
+        .stdout("b\n")
+        .stderr("Error: syntax error near unexpected token '&&'\n");
+}
+
+#[test]
+fn test_quotes_keep_spaces() {
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.env("RUSTCLI_GREETING", "hi  there").write_stdin(concat!(
+        "echo \"hello   world\"\n",
+        "echo 'single $RUSTCLI_GREETING' \"double $RUSTCLI_GREETING\"\n",
+        "echo escaped\\ \\ space \\$RUSTCLI_GREETING\n",
+    ));
+    cmd.assert().success().stdout(concat!(
+        "hello   world\n",
+        "single $RUSTCLI_GREETING double hi  there\n",
+        "escaped  space $RUSTCLI_GREETING\n",
+    ));
+}
+
+#[test]
+fn test_quoted_file_names() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .write_stdin("touch \"my file.txt\"\necho 'a | b; c > d' > \"out file.txt\"\n");
+    cmd.assert().success().stdout("");
+
+    let mut names: Vec<String> = std::fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["my file.txt", "out file.txt"]);
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.path().join("out file.txt")).unwrap(),
+        "a | b; c > d\n"
+    );
+}
+
+#[test]
+fn test_unterminated_quote_is_an_error() {
+    let mut cmd = Command::cargo_bin("cli-shell").unwrap();
+    cmd.write_stdin("echo \"oops\necho after\n");
+    cmd.assert()
+        .success()
+        .stdout("after\n")
+        .stderr("Error: unterminated double quote\n");
+}
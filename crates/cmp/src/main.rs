@@ -0,0 +1,241 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+
+#[derive(Parser, Debug)]
+#[command(name = "cmp")]
+#[command(about = "Compare two files byte by byte", long_about = None)]
+#[command(version)]
+struct Args {
+    /// Print the offset and differing byte values (in octal) for every difference
+    #[arg(short = 'l', long = "verbose", conflicts_with = "silent")]
+    list: bool,
+
+    /// Print nothing; only report through the exit status
+    #[arg(short = 's', long = "silent", visible_alias = "quiet")]
+    silent: bool,
+
+    /// First file (use '-' for stdin)
+    file1: String,
+
+    /// Second file (use '-' for stdin)
+    #[arg(default_value = "-")]
+    file2: String,
+}
+
+/// How the two inputs compared.
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Identical,
+    /// The first differing byte, counted from 1, and the line it is on
+    Differ {
+        byte: u64,
+        line: u64,
+    },
+    /// The input at `index` (0 or 1) ended while the other carried on,
+    /// after `bytes` equal bytes on `lines` lines
+    Eof {
+        index: usize,
+        bytes: u64,
+        lines: u64,
+    },
+}
+
+fn main() -> ExitCode {
+    // As with GNU cmp, 0 means identical, 1 means different, and any
+    // trouble, runtime or usage, exits 2
+    match run() {
+        Ok(Outcome::Identical) => ExitCode::SUCCESS,
+        Ok(_) => ExitCode::from(common::exit::FAILURE),
+        Err(error) => {
+            common::exit::report("cmp", Err::<(), _>(error));
+            ExitCode::from(common::exit::USAGE)
+        }
+    }
+}
+
+fn run() -> Result<Outcome> {
+    let args = Args::parse();
+
+    // A file, or stdin, is always equal to itself
+    if args.file1 == args.file2 {
+        return Ok(Outcome::Identical);
+    }
+
+    let reader1 = common::io::open_input(&args.file1)
+        .with_context(|| format!("Failed to open file: {}", args.file1))?;
+    let reader2 = common::io::open_input(&args.file2)
+        .with_context(|| format!("Failed to open file: {}", args.file2))?;
+
+    let mut stdout = common::io::stdout_writer();
+    let outcome = compare(reader1, reader2, args.list, &mut stdout)?;
+
+    if !args.silent {
+        match &outcome {
+            Outcome::Identical => {}
+            Outcome::Differ { byte, line } => {
+                if !args.list {
+                    writeln!(
+                        stdout,
+                        "{} {} differ: byte {}, line {}",
+                        args.file1, args.file2, byte, line
+                    )?;
+                }
+            }
+            Outcome::Eof {
+                index,
+                bytes,
+                lines,
+            } => {
+                stdout.flush()?;
+                let name = [&args.file1, &args.file2][*index];
+                if *bytes == 0 {
+                    eprintln!("cmp: EOF on {} which is empty", name);
+                } else if args.list {
+                    eprintln!("cmp: EOF on {} after byte {}", name, bytes);
+                } else {
+                    eprintln!("cmp: EOF on {} after byte {}, line {}", name, bytes, lines);
+                }
+            }
+        }
+    }
+
+    stdout.flush()?;
+    Ok(outcome)
+}
+
+/// Compares the two inputs a buffer at a time. Without `list` it stops at
+/// the first difference; with it, every differing byte is written to
+/// `writer` as `OFFSET LEFT RIGHT`, with the byte values in octal.
+fn compare<A: BufRead, B: BufRead, W: Write>(
+    mut left: A,
+    mut right: B,
+    list: bool,
+    writer: &mut W,
+) -> io::Result<Outcome> {
+    let mut offset = 0u64;
+    let mut lines = 0u64;
+    let mut in_line = false;
+    let mut first_difference = None;
+
+    loop {
+        let a = left.fill_buf()?;
+        let b = right.fill_buf()?;
+
+        match (a.is_empty(), b.is_empty()) {
+            (true, true) => return Ok(first_difference.unwrap_or(Outcome::Identical)),
+            (true, false) | (false, true) => {
+                let index = if a.is_empty() { 0 } else { 1 };
+                return Ok(Outcome::Eof {
+                    index,
+                    bytes: offset,
+                    lines: lines + u64::from(in_line),
+                });
+            }
+            (false, false) => {}
+        }
+
+        let length = a.len().min(b.len());
+        for (position, (&x, &y)) in a[..length].iter().zip(&b[..length]).enumerate() {
+            if x != y {
+                let byte = offset + position as u64 + 1;
+                if !list {
+                    return Ok(Outcome::Differ {
+                        byte,
+                        line: lines + 1,
+                    });
+                }
+                writeln!(writer, "{} {:>3o} {:>3o}", byte, x, y)?;
+                first_difference.get_or_insert(Outcome::Differ {
+                    byte,
+                    line: lines + 1,
+                });
+            }
+            if x == b'\n' {
+                lines += 1;
+            }
+        }
+
+        in_line = a[length - 1] != b'\n';
+        left.consume(length);
+        right.consume(length);
+        offset += length as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_compare(left: &[u8], right: &[u8], list: bool) -> (Outcome, String) {
+        let mut output = Vec::new();
+        let outcome = compare(left, right, list, &mut output).unwrap();
+        (outcome, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_identical() {
+        assert_eq!(run_compare(b"abc\n", b"abc\n", false).0, Outcome::Identical);
+        assert_eq!(run_compare(b"", b"", true).0, Outcome::Identical);
+    }
+
+    #[test]
+    fn test_first_difference_byte_and_line() {
+        let (outcome, output) = run_compare(b"one\ntwo\n", b"one\ntwX\n", false);
+        assert_eq!(outcome, Outcome::Differ { byte: 7, line: 2 });
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_list_every_difference() {
+        let (outcome, output) = run_compare(b"abcd", b"aXcY", true);
+        assert_eq!(outcome, Outcome::Differ { byte: 2, line: 1 });
+        assert_eq!(output, "2 142 130\n4 144 131\n");
+    }
+
+    #[test]
+    fn test_shorter_input_hits_eof() {
+        let (outcome, _) = run_compare(b"ab\ncd", b"ab\n", false);
+        assert_eq!(
+            outcome,
+            Outcome::Eof {
+                index: 1,
+                bytes: 3,
+                lines: 1
+            }
+        );
+
+        let (outcome, _) = run_compare(b"ab\nc", b"ab\ncd", false);
+        assert_eq!(
+            outcome,
+            Outcome::Eof {
+                index: 0,
+                bytes: 4,
+                lines: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_compares_across_buffer_boundaries() {
+        let left = vec![b'x'; 20_000];
+        let mut right = left.clone();
+        right[15_000] = b'y';
+
+        let outcome = compare(
+            io::BufReader::with_capacity(4096, &left[..]),
+            io::BufReader::with_capacity(1000, &right[..]),
+            false,
+            &mut Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            outcome,
+            Outcome::Differ {
+                byte: 15_001,
+                line: 1
+            }
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+fn write_files(first: &str, second: &str) -> (TempDir, String, String) {
+    let temp_dir = TempDir::new().unwrap();
+    let path1 = temp_dir.path().join("a.txt");
+    let path2 = temp_dir.path().join("b.txt");
+    fs::write(&path1, first).unwrap();
+    fs::write(&path2, second).unwrap();
+    let path1 = path1.to_str().unwrap().to_string();
+    let path2 = path2.to_str().unwrap().to_string();
+    (temp_dir, path1, path2)
+}
+
+#[test]
+fn test_cmp_identical_files() {
+    let (_temp_dir, a, b) = write_files("same\ncontent\n", "same\ncontent\n");
+
+    let mut cmd = Command::cargo_bin("cmp").unwrap();
+    cmd.arg(&a).arg(&b);
+    cmd.assert().code(0).stdout("");
+}
+
+#[test]
+fn test_cmp_reports_first_difference() {
+    let (_temp_dir, a, b) = write_files("line one\nline two\n", "line one\nline TWO\n");
+
+    let mut cmd = Command::cargo_bin("cmp").unwrap();
+    cmd.arg(&a).arg(&b);
+    cmd.assert()
+        .code(1)
+        .stdout(format!("{} {} differ: byte 15, line 2\n", a, b));
+}
+
+#[test]
+fn test_cmp_silent() {
+    let (_temp_dir, a, b) = write_files("abc", "abd");
+
+    let mut cmd = Command::cargo_bin("cmp").unwrap();
+    cmd.arg("-s").arg(&a).arg(&b);
+    cmd.assert().code(1).stdout("").stderr("");
+}
+
+#[test]
+fn test_cmp_eof_on_shorter_file() {
+    let (_temp_dir, a, b) = write_files("abc\n", "abc\nmore\n");
+
+    let mut cmd = Command::cargo_bin("cmp").unwrap();
+    cmd.arg(&a).arg(&b);
+    cmd.assert()
+        .code(1)
+        .stdout("")
+        .stderr(format!("cmp: EOF on {} after byte 4, line 1\n", a));
+}
+
+#[test]
+fn test_cmp_missing_file_is_trouble() {
+    let (_temp_dir, a, _) = write_files("", "");
+
+    let mut cmd = Command::cargo_bin("cmp").unwrap();
+    cmd.arg(&a).arg("no_such_file_12345");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("no_such_file_12345"));
+}
@@ -0,0 +1,379 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use common::exit::UsageError;
+use common::io::read_record;
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+
+#[derive(Parser, Debug)]
+#[command(name = "comm")]
+#[command(about = "Compare two sorted files line by line", long_about = None)]
+#[command(version)]
+struct Args {
+    /// Suppress column 1 (lines unique to FILE1)
+    #[arg(short = '1')]
+    suppress_first: bool,
+
+    /// Suppress column 2 (lines unique to FILE2)
+    #[arg(short = '2')]
+    suppress_second: bool,
+
+    /// Suppress column 3 (lines that appear in both files)
+    #[arg(short = '3')]
+    suppress_common: bool,
+
+    /// Fail if the input is not correctly sorted
+    #[arg(long = "check-order", conflicts_with = "nocheck_order")]
+    check_order: bool,
+
+    /// Do not check that the input is correctly sorted
+    #[arg(long = "nocheck-order")]
+    nocheck_order: bool,
+
+    /// Line delimiter is NUL, not newline
+    #[arg(short = 'z', long = "zero-terminated")]
+    zero_terminated: bool,
+
+    /// First sorted file (use '-' for stdin)
+    file1: String,
+
+    /// Second sorted file (use '-' for stdin)
+    file2: String,
+}
+
+fn main() -> ExitCode {
+    common::exit::report("comm", run())
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse();
+
+    if args.file1 == "-" && args.file2 == "-" {
+        anyhow::bail!(UsageError::new("only one of FILE1 and FILE2 can be standard input"));
+    }
+
+    let reader1 = common::io::open_input(&args.file1)
+        .with_context(|| format!("Failed to open file: {}", args.file1))?;
+    let reader2 = common::io::open_input(&args.file2)
+        .with_context(|| format!("Failed to open file: {}", args.file2))?;
+
+    let columns = Columns {
+        first: !args.suppress_first,
+        second: !args.suppress_second,
+        common: !args.suppress_common,
+        separator: common::io::record_separator(args.zero_terminated),
+    };
+
+    let order_check = if args.check_order {
+        OrderCheck::Strict
+    } else if args.nocheck_order {
+        OrderCheck::Disabled
+    } else {
+        OrderCheck::Warn
+    };
+    let mut checker = OrderChecker::new(order_check);
+
+    let mut stdout = common::io::stdout_writer();
+    let result = compare(
+        reader1,
+        reader2,
+        &mut stdout,
+        &columns,
+        &mut checker,
+        &mut io::stderr(),
+    );
+    stdout.flush()?;
+    result?;
+
+    Ok(())
+}
+
+/// Which of the three output columns are shown, and how records end.
+struct Columns {
+    first: bool,
+    second: bool,
+    common: bool,
+    separator: u8,
+}
+
+impl Columns {
+    fn write_first<W: Write>(&self, writer: &mut W, line: &[u8]) -> io::Result<()> {
+        if self.first {
+            write_line(writer, 0, line, self.separator)?;
+        }
+        Ok(())
+    }
+
+    fn write_second<W: Write>(&self, writer: &mut W, line: &[u8]) -> io::Result<()> {
+        if self.second {
+            write_line(writer, usize::from(self.first), line, self.separator)?;
+        }
+        Ok(())
+    }
+
+    fn write_common<W: Write>(&self, writer: &mut W, line: &[u8]) -> io::Result<()> {
+        if self.common {
+            let indent = usize::from(self.first) + usize::from(self.second);
+            write_line(writer, indent, line, self.separator)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_line<W: Write>(
+    writer: &mut W,
+    indent: usize,
+    line: &[u8],
+    separator: u8,
+) -> io::Result<()> {
+    for _ in 0..indent {
+        writer.write_all(b"\t")?;
+    }
+    writer.write_all(line)?;
+    writer.write_all(&[separator])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OrderCheck {
+    /// Warn once per file and keep going (the default)
+    Warn,
+    /// Stop with an error at the first out-of-order line
+    Strict,
+    /// Never check the order (--nocheck-order)
+    Disabled,
+}
+
+/// Tracks whether each input has been seen out of order.
+struct OrderChecker {
+    mode: OrderCheck,
+    reported: [bool; 2],
+}
+
+impl OrderChecker {
+    fn new(mode: OrderCheck) -> Self {
+        Self {
+            mode,
+            reported: [false; 2],
+        }
+    }
+
+    /// Reads the next record of input `file` (0 or 1) into `current`,
+    /// checking it sorts at or after the record it replaces.
+    fn advance<R: BufRead, E: Write>(
+        &mut self,
+        file: usize,
+        reader: &mut R,
+        separator: u8,
+        current: &mut Option<Vec<u8>>,
+        warnings: &mut E,
+    ) -> io::Result<()> {
+        let next = read_record(reader, separator)?;
+
+        if let (Some(previous), Some(line)) = (current.as_ref(), next.as_ref()) {
+            if line < previous {
+                self.report(file, warnings)?;
+            }
+        }
+
+        *current = next;
+        Ok(())
+    }
+
+    fn report<E: Write>(&mut self, file: usize, warnings: &mut E) -> io::Result<()> {
+        let message = format!("file {} is not in sorted order", file + 1);
+
+        match self.mode {
+            OrderCheck::Disabled => Ok(()),
+            OrderCheck::Strict => Err(io::Error::new(io::ErrorKind::InvalidData, message)),
+            OrderCheck::Warn => {
+                if !self.reported[file] {
+                    self.reported[file] = true;
+                    writeln!(warnings, "comm: {}", message)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Walks both sorted inputs in step, emitting each line in the column
+/// for the file(s) it appears in.
+fn compare<A: BufRead, B: BufRead, W: Write, E: Write>(
+    mut reader1: A,
+    mut reader2: B,
+    writer: &mut W,
+    columns: &Columns,
+    checker: &mut OrderChecker,
+    warnings: &mut E,
+) -> io::Result<()> {
+    let separator = columns.separator;
+    let mut line1 = read_record(&mut reader1, separator)?;
+    let mut line2 = read_record(&mut reader2, separator)?;
+
+    loop {
+        match (&line1, &line2) {
+            (None, None) => break,
+            (Some(a), None) => {
+                columns.write_first(writer, a)?;
+                checker.advance(0, &mut reader1, separator, &mut line1, warnings)?;
+            }
+            (None, Some(b)) => {
+                columns.write_second(writer, b)?;
+                checker.advance(1, &mut reader2, separator, &mut line2, warnings)?;
+            }
+            (Some(a), Some(b)) => match a.cmp(b) {
+                Ordering::Less => {
+                    columns.write_first(writer, a)?;
+                    checker.advance(0, &mut reader1, separator, &mut line1, warnings)?;
+                }
+                Ordering::Greater => {
+                    columns.write_second(writer, b)?;
+                    checker.advance(1, &mut reader2, separator, &mut line2, warnings)?;
+                }
+                Ordering::Equal => {
+                    columns.write_common(writer, a)?;
+                    checker.advance(0, &mut reader1, separator, &mut line1, warnings)?;
+                    checker.advance(1, &mut reader2, separator, &mut line2, warnings)?;
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE1: &[u8] = b"apple\nbanana\ncherry\n";
+    const FILE2: &[u8] = b"banana\ncherry\ndate\n";
+
+    fn compare_columns(first: bool, second: bool, common: bool) -> String {
+        let columns = Columns {
+            first,
+            second,
+            common,
+            separator: b'\n',
+        };
+        let mut output = Vec::new();
+        let mut checker = OrderChecker::new(OrderCheck::Warn);
+        compare(FILE1, FILE2, &mut output, &columns, &mut checker, &mut io::sink()).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_three_columns() {
+        assert_eq!(
+            compare_columns(true, true, true),
+            "apple\n\t\tbanana\n\t\tcherry\n\tdate\n"
+        );
+    }
+
+    #[test]
+    fn test_suppress_first() {
+        assert_eq!(compare_columns(false, true, true), "\tbanana\n\tcherry\ndate\n");
+    }
+
+    #[test]
+    fn test_suppress_second() {
+        assert_eq!(compare_columns(true, false, true), "apple\n\tbanana\n\tcherry\n");
+    }
+
+    #[test]
+    fn test_suppress_common() {
+        assert_eq!(compare_columns(true, true, false), "apple\n\tdate\n");
+    }
+
+    #[test]
+    fn test_only_common() {
+        assert_eq!(compare_columns(false, false, true), "banana\ncherry\n");
+    }
+
+    #[test]
+    fn test_zero_terminated() {
+        let columns = Columns {
+            first: true,
+            second: true,
+            common: true,
+            separator: common::io::record_separator(true),
+        };
+        let mut output = Vec::new();
+        let mut checker = OrderChecker::new(OrderCheck::Warn);
+        compare(
+            &b"a\nb\0c\0"[..],
+            &b"c\0d\0"[..],
+            &mut output,
+            &columns,
+            &mut checker,
+            &mut io::sink(),
+        )
+        .unwrap();
+        assert_eq!(output, b"a\nb\0\t\tc\0\td\0");
+    }
+
+    #[test]
+    fn test_missing_trailing_newline() {
+        let columns = Columns {
+            first: true,
+            second: true,
+            common: true,
+            separator: b'\n',
+        };
+        let mut output = Vec::new();
+        let mut checker = OrderChecker::new(OrderCheck::Warn);
+        compare(
+            &b"a\nb"[..],
+            &b"b\n"[..],
+            &mut output,
+            &columns,
+            &mut checker,
+            &mut io::sink(),
+        )
+        .unwrap();
+        assert_eq!(output, b"a\n\t\tb\n");
+    }
+
+    fn run_unsorted(mode: OrderCheck) -> (io::Result<()>, String) {
+        let columns = Columns {
+            first: true,
+            second: true,
+            common: true,
+            separator: b'\n',
+        };
+        let mut checker = OrderChecker::new(mode);
+        let mut warnings = Vec::new();
+        let result = compare(
+            &b"b\na\nd\nc\n"[..],
+            &b"a\nb\n"[..],
+            &mut io::sink(),
+            &columns,
+            &mut checker,
+            &mut warnings,
+        );
+        (result, String::from_utf8(warnings).unwrap())
+    }
+
+    #[test]
+    fn test_unsorted_input_warns_once() {
+        let (result, warnings) = run_unsorted(OrderCheck::Warn);
+        assert!(result.is_ok());
+        assert_eq!(warnings, "comm: file 1 is not in sorted order\n");
+    }
+
+    #[test]
+    fn test_check_order_is_an_error() {
+        let (result, warnings) = run_unsorted(OrderCheck::Strict);
+        let error = result.unwrap_err();
+        assert_eq!(error.to_string(), "file 1 is not in sorted order");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_nocheck_order_is_silent() {
+        let (result, warnings) = run_unsorted(OrderCheck::Disabled);
+        assert!(result.is_ok());
+        assert!(warnings.is_empty());
+    }
+}
@@ -22,6 +22,38 @@ impl Default for ColorConfig {
     }
 }
 
+/// Renders `s` with its escape codes whether or not colored output is
+/// turned on for the process, for callers that decide when to color by
+/// themselves.
+pub fn paint(s: &colored::ColoredString) -> String {
+    use colored::Styles;
+
+    const STYLES: [(Styles, &str); 8] = [
+        (Styles::Bold, "1"),
+        (Styles::Dimmed, "2"),
+        (Styles::Italic, "3"),
+        (Styles::Underline, "4"),
+        (Styles::Blink, "5"),
+        (Styles::Reversed, "7"),
+        (Styles::Hidden, "8"),
+        (Styles::Strikethrough, "9"),
+    ];
+
+    // Same order as colored itself: styles, then background, then foreground
+    let mut codes: Vec<String> = STYLES
+        .iter()
+        .filter(|(style, _)| s.style.contains(*style))
+        .map(|(_, code)| code.to_string())
+        .collect();
+    codes.extend(s.bgcolor.map(|color| color.to_bg_str().into_owned()));
+    codes.extend(s.fgcolor.map(|color| color.to_fg_str().into_owned()));
+
+    if codes.is_empty() {
+        return s.to_string();
+    }
+    format!("\x1b[{}m{}\x1b[0m", codes.join(";"), &**s)
+}
+
 /// Common color schemes for different file types.
 pub mod schemes {
     use colored::*;
@@ -63,5 +95,13 @@ mod tests {
         let config = ColorConfig::new(false);
         assert!(!config.is_enabled());
     }
+
+    #[test]
+    fn test_paint_ignores_override() {
+        colored::control::set_override(false);
+        assert_eq!(paint(&schemes::directory("dir")), "\x1b[1;34mdir\x1b[0m");
+        assert_eq!(paint(&schemes::symlink("link")), "\x1b[36mlink\x1b[0m");
+        colored::control::unset_override();
+    }
 }
 
@@ -0,0 +1,196 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// The result of interpreting the escapes in some text.
+#[derive(Debug, Default, PartialEq)]
+pub struct Escaped {
+    pub bytes: Vec<u8>,
+    /// A `\c` cut the text short: nothing more should be printed, not even
+    /// a trailing newline
+    pub stopped: bool,
+}
+
+/// Interprets backslash escapes such as `\n` and `\t` in `text`.
+///
+/// Like [`interpret_escapes_bytes`], but for callers that want text: bytes
+/// that don't form valid UTF-8 come out as U+FFFD, and the text simply
+/// ends at a `\c`.
+pub fn interpret_escapes(text: &str) -> String {
+    String::from_utf8_lossy(&interpret_escapes_bytes(text).bytes).into_owned()
+}
+
+/// Interprets backslash escapes in `text`, returning the raw bytes they
+/// stand for.
+///
+/// Besides the single-letter escapes, `\xHH` takes one or two hex digits,
+/// and `\0NNN` or `\NNN` up to three octal digits, as a byte value, which
+/// is cut down to a byte if it is larger. `\uHHHH` and `\UHHHHHHHH` take
+/// exactly four and eight hex digits as a Unicode code point, written out
+/// as UTF-8. A `\x` with no hex digits, a `\u` or `\U` without a valid
+/// code point, unrecognized escapes, and a trailing lone backslash are
+/// kept as written. `\c` drops the rest of the text and sets [`Escaped::stopped`].
+/// Used by `echo -e`.
+pub fn interpret_escapes_bytes(text: &str) -> Escaped {
+    let mut result = Vec::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            push_char(&mut result, ch);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push(b'\n'),
+            Some('t') => result.push(b'\t'),
+            Some('r') => result.push(b'\r'),
+            Some('\\') => result.push(b'\\'),
+            Some('a') => result.push(0x07), // alert (bell)
+            Some('b') => result.push(0x08), // backspace
+            Some('f') => result.push(0x0C), // form feed
+            Some('v') => result.push(0x0B), // vertical tab
+            Some('c') => {
+                return Escaped {
+                    bytes: result,
+                    stopped: true,
+                }
+            }
+            Some('x') => match take_digits(&mut chars, 16, 2) {
+                (_, 0) => result.extend_from_slice(b"\\x"),
+                (value, _) => result.push(value as u8),
+            },
+            Some(letter @ ('u' | 'U')) => {
+                let length = if letter == 'u' { 4 } else { 8 };
+                match take_code_point(&mut chars, length) {
+                    Some(ch) => push_char(&mut result, ch),
+                    None => {
+                        result.push(b'\\');
+                        push_char(&mut result, letter);
+                    }
+                }
+            }
+            Some('0') => result.push(take_digits(&mut chars, 8, 3).0 as u8),
+            Some(first @ '1'..='7') => {
+                let first = first.to_digit(8).unwrap_or_default();
+                let (rest, count) = take_digits(&mut chars, 8, 2);
+                result.push((first * 8u32.pow(count) + rest) as u8);
+            }
+            Some(next) => {
+                result.push(b'\\');
+                push_char(&mut result, next);
+            }
+            None => result.push(b'\\'),
+        }
+    }
+
+    Escaped {
+        bytes: result,
+        stopped: false,
+    }
+}
+
+fn push_char(bytes: &mut Vec<u8>, ch: char) {
+    bytes.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+}
+
+/// Takes exactly `length` hex digits from the front of `chars` if they
+/// make a valid code point, which surrogates are not, and leaves `chars`
+/// alone otherwise.
+fn take_code_point(chars: &mut Peekable<Chars>, length: u32) -> Option<char> {
+    let mut lookahead = chars.clone();
+    let (value, count) = take_digits(&mut lookahead, 16, length);
+    let ch = char::from_u32(value).filter(|_| count == length)?;
+    *chars = lookahead;
+    Some(ch)
+}
+
+/// Takes up to `max` digits in `radix` from the front of `chars`,
+/// returning their value and how many there were.
+fn take_digits(chars: &mut Peekable<Chars>, radix: u32, max: u32) -> (u32, u32) {
+    let mut value = 0;
+    let mut count = 0;
+
+    while count < max {
+        let Some(digit) = chars.peek().and_then(|ch| ch.to_digit(radix)) else {
+            break;
+        };
+        chars.next();
+        value = value * radix + digit;
+        count += 1;
+    }
+
+    (value, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_escapes() {
+        assert_eq!(interpret_escapes("a\\nb\\tc\\\\d"), "a\nb\tc\\d");
+        assert_eq!(interpret_escapes("\\a\\b\\f\\v\\0"), "\x07\x08\x0C\x0B\0");
+    }
+
+    #[test]
+    fn test_unknown_and_trailing_backslash() {
+        assert_eq!(interpret_escapes("\\q"), "\\q");
+        assert_eq!(interpret_escapes("end\\"), "end\\");
+    }
+
+    #[test]
+    fn test_hex_escapes() {
+        assert_eq!(interpret_escapes("\\x41"), "A");
+        assert_eq!(interpret_escapes("\\x4"), "\x04");
+        // At most two digits are taken
+        assert_eq!(interpret_escapes("\\x414"), "A4");
+        assert_eq!(interpret_escapes("\\xg"), "\\xg");
+        assert_eq!(interpret_escapes_bytes("\\xff").bytes, [0xFF]);
+    }
+
+    #[test]
+    fn test_octal_escapes() {
+        assert_eq!(interpret_escapes("\\101"), "A");
+        assert_eq!(interpret_escapes("\\0101"), "A");
+        assert_eq!(interpret_escapes("\\01019"), "A9");
+        assert_eq!(interpret_escapes("\\08"), "\08");
+        assert_eq!(interpret_escapes("\\7"), "\x07");
+        // 0o777 is 511, which is cut down to a byte
+        assert_eq!(interpret_escapes_bytes("\\0777").bytes, [0xFF]);
+    }
+
+    #[test]
+    fn test_bytes_keep_other_text_as_utf8() {
+        assert_eq!(interpret_escapes_bytes("é\\n").bytes, "é\n".as_bytes());
+        // Escaped bytes that spell out UTF-8 come back as text
+        assert_eq!(interpret_escapes("\\xc3\\xa9"), "é");
+    }
+
+    #[test]
+    fn test_unicode_escapes() {
+        assert_eq!(interpret_escapes("caf\\u00e9"), "café");
+        assert_eq!(interpret_escapes("\\U0001F600!"), "\u{1F600}!");
+        // Only four digits are taken
+        assert_eq!(interpret_escapes("\\u00411"), "A1");
+    }
+
+    #[test]
+    fn test_invalid_unicode_escapes_stay_literal() {
+        assert_eq!(interpret_escapes("\\ud800"), "\\ud800");
+        assert_eq!(interpret_escapes("\\u12"), "\\u12");
+        assert_eq!(interpret_escapes("\\U00110000"), "\\U00110000");
+    }
+
+    #[test]
+    fn test_c_stops_the_text() {
+        assert_eq!(
+            interpret_escapes_bytes("a\\cb\\n"),
+            Escaped {
+                bytes: b"a".to_vec(),
+                stopped: true
+            }
+        );
+        assert!(!interpret_escapes_bytes("a\\nb").stopped);
+        assert_eq!(interpret_escapes("a\\cb"), "a");
+    }
+}
@@ -0,0 +1,180 @@
+//! Exit statuses and error reporting shared by every binary.
+//!
+//! Each binary keeps its logic in a `run` function returning a `Result`
+//! and hands that result to [`report`], which prints the error and picks
+//! the exit status:
+//!
+//! - `0` on success
+//! - `1` for runtime failures such as a missing file
+//! - `2` for usage errors: bad options (reported by clap itself), invalid
+//!   argument values, or operands that don't make sense together
+//!
+//! Setting `RUST_CLI_DEBUG` to anything but `0` prints every error in the
+//! chain on its own line, with its debug representation, instead of the
+//! usual one-line message.
+
+use crate::error::CommonError;
+use std::error::Error;
+use std::fmt;
+use std::process::ExitCode;
+
+/// Exit status for runtime failures.
+pub const FAILURE: u8 = 1;
+
+/// Exit status for invalid command lines.
+pub const USAGE: u8 = 2;
+
+/// Environment variable that turns on the full error trace.
+pub const DEBUG_VAR: &str = "RUST_CLI_DEBUG";
+
+/// An error caused by how the program was invoked rather than by anything
+/// that went wrong while running it. Reported with exit status 2.
+#[derive(Debug)]
+pub struct UsageError(pub String);
+
+impl UsageError {
+    pub fn new(message: impl Into<String>) -> Self {
+        UsageError(message.into())
+    }
+}
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for UsageError {}
+
+/// Returns whether `RUST_CLI_DEBUG` asks for full error traces.
+pub fn debug_enabled() -> bool {
+    std::env::var_os(DEBUG_VAR).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Prints the error in `result`, if any, as `program: message` on stderr
+/// and returns the matching exit status.
+pub fn report<E: AsRef<dyn Error + 'static>>(program: &str, result: Result<(), E>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            let error = error.as_ref();
+            let message = if debug_enabled() {
+                format_trace(error)
+            } else {
+                format_chain(error)
+            };
+            eprintln!("{}: {}", program, message);
+            ExitCode::from(exit_status(error))
+        }
+    }
+}
+
+/// The exit status for `error`: [`USAGE`] if anything in its chain is a
+/// usage error, [`FAILURE`] otherwise.
+pub fn exit_status(error: &(dyn Error + 'static)) -> u8 {
+    if chain(error).any(is_usage_error) {
+        USAGE
+    } else {
+        FAILURE
+    }
+}
+
+fn is_usage_error(error: &(dyn Error + 'static)) -> bool {
+    error.is::<UsageError>()
+        || matches!(
+            error.downcast_ref::<CommonError>(),
+            Some(CommonError::InvalidArgument(_))
+        )
+}
+
+fn chain<'a>(error: &'a (dyn Error + 'static)) -> impl Iterator<Item = &'a (dyn Error + 'static)> {
+    std::iter::successors(Some(error), |&error| error.source())
+}
+
+/// Joins the messages of the whole chain, e.g.
+/// `Failed to open file: a.txt: No such file or directory (os error 2)`.
+fn format_chain(error: &(dyn Error + 'static)) -> String {
+    chain(error)
+        .map(|error| error.to_string())
+        .collect::<Vec<_>>()
+        .join(": ")
+}
+
+/// One line per error in the chain, each followed by its debug form.
+fn format_trace(error: &(dyn Error + 'static)) -> String {
+    let mut output = format!("{}\n    {:?}", error, error);
+
+    for (index, cause) in chain(error).skip(1).enumerate() {
+        if index == 0 {
+            output.push_str("\n\nCaused by:");
+        }
+        output.push_str(&format!("\n{:>5}: {}\n       {:?}", index, cause, cause));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[derive(Debug)]
+    struct Context {
+        message: &'static str,
+        source: Box<dyn Error + 'static>,
+    }
+
+    impl fmt::Display for Context {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.message)
+        }
+    }
+
+    impl Error for Context {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(self.source.as_ref())
+        }
+    }
+
+    fn wrapped(source: impl Error + 'static) -> Context {
+        Context {
+            message: "Failed to open file: a.txt",
+            source: Box::new(source),
+        }
+    }
+
+    #[test]
+    fn test_runtime_errors_exit_1() {
+        let error = io::Error::new(io::ErrorKind::NotFound, "No such file or directory");
+        assert_eq!(exit_status(&error), FAILURE);
+        assert_eq!(exit_status(&wrapped(error)), FAILURE);
+    }
+
+    #[test]
+    fn test_usage_errors_exit_2() {
+        assert_eq!(exit_status(&UsageError::new("bad width")), USAGE);
+        assert_eq!(exit_status(&wrapped(UsageError::new("bad width"))), USAGE);
+
+        let invalid = CommonError::InvalidArgument("invalid size 'x'".to_string());
+        assert_eq!(exit_status(&invalid), USAGE);
+
+        let io = CommonError::Io(io::Error::other("disk full"));
+        assert_eq!(exit_status(&io), FAILURE);
+    }
+
+    #[test]
+    fn test_format_chain() {
+        let error = wrapped(io::Error::new(io::ErrorKind::NotFound, "gone"));
+        assert_eq!(format_chain(&error), "Failed to open file: a.txt: gone");
+    }
+
+    #[test]
+    fn test_format_trace_lists_causes() {
+        let error = wrapped(io::Error::new(io::ErrorKind::NotFound, "gone"));
+        let trace = format_trace(&error);
+        assert!(trace.starts_with("Failed to open file: a.txt\n"));
+        assert!(trace.contains("Caused by:\n    0: gone\n"));
+        assert!(trace.contains("NotFound"));
+    }
+}
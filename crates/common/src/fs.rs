@@ -0,0 +1,218 @@
+//! File system operations shared by the tools that move files around.
+
+use filetime::FileTime;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Moves `source` to `destination`, replacing it if it exists the way
+/// `fs::rename` would.
+///
+/// A plain rename is tried first. When the two paths are on different file
+/// systems, the source is copied (directories recursively), keeping
+/// permissions and timestamps, and only removed once the copy is complete.
+/// If the copy fails, the partial copy is cleaned up and both the source
+/// and any existing destination are left as they were.
+pub fn move_path(source: &Path, destination: &Path) -> io::Result<()> {
+    move_with(source, destination, |from, to| fs::rename(from, to))
+}
+
+/// [`move_path`] with the first rename attempt swapped out, so tests can
+/// fake a move across file systems.
+fn move_with(
+    source: &Path,
+    destination: &Path,
+    rename: impl FnOnce(&Path, &Path) -> io::Result<()>,
+) -> io::Result<()> {
+    match rename(source, destination) {
+        Err(e) if is_cross_device(&e) => copy_then_remove(source, destination),
+        result => result,
+    }
+}
+
+/// Returns whether `error` is the `EXDEV` a rename across file systems
+/// fails with.
+pub fn is_cross_device(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::CrossesDevices
+}
+
+fn copy_then_remove(source: &Path, destination: &Path) -> io::Result<()> {
+    // Copy next to the destination first, so that a failed copy never
+    // touches it and a finished one replaces it in a single rename
+    let staging = staging_path(destination);
+
+    let copied = copy_tree(source, &staging).and_then(|()| fs::rename(&staging, destination));
+    if let Err(e) = copied {
+        let _ = remove_tree(&staging);
+        return Err(e);
+    }
+
+    remove_tree(source)
+}
+
+/// A hidden name beside `destination` to copy into before renaming.
+fn staging_path(destination: &Path) -> PathBuf {
+    let name = destination
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    destination.with_file_name(format!(".{}.partial-{}", name, std::process::id()))
+}
+
+/// Copies a file, symlink, or whole directory tree, keeping permissions
+/// and access and modification times. Symlinks are copied as links, never
+/// followed.
+fn copy_tree(source: &Path, destination: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(source)?;
+    let atime = FileTime::from_last_access_time(&metadata);
+    let mtime = FileTime::from_last_modification_time(&metadata);
+
+    if metadata.is_dir() {
+        fs::create_dir(destination)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_tree(&entry.path(), &destination.join(entry.file_name()))?;
+        }
+        // Only now that the entries are in place, as adding them bumps the
+        // directory's mtime; and before the permissions, which may drop
+        // write access
+        filetime::set_file_times(destination, atime, mtime)?;
+        fs::set_permissions(destination, metadata.permissions())
+    } else if metadata.file_type().is_symlink() {
+        copy_symlink(source, destination)?;
+        filetime::set_symlink_file_times(destination, atime, mtime)
+    } else {
+        // fs::copy carries the permission bits over
+        fs::copy(source, destination)?;
+        filetime::set_file_times(destination, atime, mtime)
+    }
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, destination)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    fs::copy(source, destination).map(|_| ())
+}
+
+fn remove_tree(path: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A rename that always fails the way one across file systems does.
+    fn cross_device(_: &Path, _: &Path) -> io::Result<()> {
+        Err(io::ErrorKind::CrossesDevices.into())
+    }
+
+    #[test]
+    fn test_move_path_renames() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("a.txt");
+        fs::write(&source, "data").unwrap();
+
+        move_path(&source, &dir.path().join("b.txt")).unwrap();
+
+        assert!(!source.exists());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("b.txt")).unwrap(),
+            "data"
+        );
+    }
+
+    #[test]
+    fn test_cross_device_move_copies_a_tree() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("tree");
+        fs::create_dir_all(source.join("sub")).unwrap();
+        fs::write(source.join("sub/file.txt"), "nested").unwrap();
+
+        let destination = dir.path().join("moved");
+        move_with(&source, &destination, cross_device).unwrap();
+
+        assert!(!source.exists());
+        assert_eq!(
+            fs::read_to_string(destination.join("sub/file.txt")).unwrap(),
+            "nested"
+        );
+    }
+
+    #[test]
+    fn test_cross_device_move_keeps_times_and_permissions() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("tree");
+        fs::create_dir(&source).unwrap();
+        fs::write(source.join("file.txt"), "data").unwrap();
+
+        let mtime = FileTime::from_unix_time(1_000_000_000, 0);
+        filetime::set_file_mtime(source.join("file.txt"), mtime).unwrap();
+        filetime::set_file_mtime(&source, mtime).unwrap();
+        let mut readonly = fs::metadata(source.join("file.txt")).unwrap().permissions();
+        readonly.set_readonly(true);
+        fs::set_permissions(source.join("file.txt"), readonly).unwrap();
+
+        let destination = dir.path().join("moved");
+        move_with(&source, &destination, cross_device).unwrap();
+
+        for path in [destination.clone(), destination.join("file.txt")] {
+            let metadata = fs::metadata(&path).unwrap();
+            assert_eq!(FileTime::from_last_modification_time(&metadata), mtime);
+        }
+        assert!(fs::metadata(destination.join("file.txt"))
+            .unwrap()
+            .permissions()
+            .readonly());
+    }
+
+    #[test]
+    fn test_cross_device_move_replaces_destination() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("new.txt");
+        let destination = dir.path().join("old.txt");
+        fs::write(&source, "new").unwrap();
+        fs::write(&destination, "old").unwrap();
+
+        move_with(&source, &destination, cross_device).unwrap();
+
+        assert!(!source.exists());
+        assert_eq!(fs::read_to_string(&destination).unwrap(), "new");
+        // No staging copy is left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_failed_copy_leaves_source_alone() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("a.txt");
+        fs::write(&source, "data").unwrap();
+
+        // The destination's parent doesn't exist, so the copy fails
+        let destination = dir.path().join("missing/b.txt");
+        assert!(move_with(&source, &destination, cross_device).is_err());
+        assert_eq!(fs::read_to_string(&source).unwrap(), "data");
+    }
+
+    #[test]
+    fn test_other_rename_errors_are_returned() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("a.txt");
+        fs::write(&source, "data").unwrap();
+
+        let result = move_with(&source, &dir.path().join("b.txt"), |_, _| {
+            Err(io::ErrorKind::PermissionDenied.into())
+        });
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+        assert!(source.exists());
+    }
+}
@@ -0,0 +1,207 @@
+//! Wildcard expansion for tools that can't count on a shell to do it,
+//! such as on Windows or inside `cli-shell`.
+//!
+//! Patterns support `*` (any run of characters), `?` (any one character),
+//! and bracket expressions like `[abc]`, `[a-z]`, and `[!0-9]`. As in a
+//! POSIX shell, wildcards never match a `/`, and a name starting with `.`
+//! is only matched by a pattern that starts with `.` too.
+
+use std::fs;
+
+/// Returns whether `text` contains any wildcard characters.
+pub fn is_pattern(text: &str) -> bool {
+    text.contains(['*', '?', '['])
+}
+
+/// Expands `pattern` against the file system, one path component at a
+/// time, relative to the current directory unless it starts with `/`.
+/// Returns the matching paths, sorted, or nothing if none match.
+pub fn expand(pattern: &str) -> Vec<String> {
+    let (mut paths, rest) = match pattern.strip_prefix('/') {
+        Some(rest) => (vec!["/".to_string()], rest),
+        None => (vec![String::new()], pattern),
+    };
+
+    for component in rest.split('/').filter(|component| !component.is_empty()) {
+        let mut next = Vec::new();
+        for base in &paths {
+            if !is_pattern(component) {
+                next.push(join(base, component));
+                continue;
+            }
+
+            let dir = if base.is_empty() { "." } else { base };
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                let Some(name) = name.to_str() else {
+                    continue;
+                };
+                if name.starts_with('.') && !component.starts_with('.') {
+                    continue;
+                }
+                if matches(component, name) {
+                    next.push(join(base, name));
+                }
+            }
+        }
+        paths = next;
+    }
+
+    // Literal components were taken on trust, so check they exist
+    paths.retain(|path| fs::symlink_metadata(path).is_ok());
+    paths.sort();
+    paths
+}
+
+fn join(base: &str, name: &str) -> String {
+    if base.is_empty() {
+        name.to_string()
+    } else if base.ends_with('/') {
+        format!("{}{}", base, name)
+    } else {
+        format!("{}/{}", base, name)
+    }
+}
+
+/// Returns whether the whole of `name` matches `pattern`.
+pub fn matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // The last `*` seen and where in the name it stopped matching, to go
+    // back to and let it take one more character when the rest fails
+    let mut star = None;
+
+    while n < name.len() {
+        let step = match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+                continue;
+            }
+            Some('?') => Some(1),
+            Some('[') => match match_class(&pattern[p..], name[n]) {
+                Some((true, length)) => Some(length),
+                Some((false, _)) => None,
+                // Never closed, so just a `[`
+                None => (name[n] == '[').then_some(1),
+            },
+            Some(&c) => (c == name[n]).then_some(1),
+            None => None,
+        };
+
+        match (step, star) {
+            (Some(length), _) => {
+                p += length;
+                n += 1;
+            }
+            (None, Some((star_p, star_n))) => {
+                p = star_p + 1;
+                n = star_n + 1;
+                star = Some((star_p, star_n + 1));
+            }
+            (None, None) => return false,
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Matches `c` against the bracket expression `pattern` starts with,
+/// returning whether it matched and how many characters the expression
+/// takes up, or `None` if there is no closing `]`.
+fn match_class(pattern: &[char], c: char) -> Option<(bool, usize)> {
+    let mut i = 1;
+    let negated = matches!(pattern.get(i), Some('!' | '^'));
+    if negated {
+        i += 1;
+    }
+
+    let mut matched = false;
+    let mut first = true;
+    loop {
+        let &start = pattern.get(i)?;
+        // A `]` straight after the opening is part of the set
+        if start == ']' && !first {
+            return Some((matched != negated, i + 1));
+        }
+        first = false;
+
+        match (pattern.get(i + 1), pattern.get(i + 2)) {
+            (Some('-'), Some(&end)) if end != ']' => {
+                matched |= start <= c && c <= end;
+                i += 3;
+            }
+            _ => {
+                matched |= start == c;
+                i += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_pattern() {
+        assert!(is_pattern("*.tmp"));
+        assert!(is_pattern("file?.txt"));
+        assert!(is_pattern("[ab].txt"));
+        assert!(!is_pattern("plain.txt"));
+    }
+
+    #[test]
+    fn test_matches_wildcards() {
+        assert!(matches("*.tmp", "a.tmp"));
+        assert!(matches("*.tmp", ".tmp"));
+        assert!(!matches("*.tmp", "a.tmp.bak"));
+        assert!(matches("a*b*c", "aXbYbZc"));
+        assert!(!matches("a*b*c", "aXbY"));
+        assert!(matches("file?.txt", "file1.txt"));
+        assert!(!matches("file?.txt", "file.txt"));
+        assert!(matches("**", ""));
+    }
+
+    #[test]
+    fn test_matches_bracket_expressions() {
+        assert!(matches("[ab].txt", "a.txt"));
+        assert!(!matches("[ab].txt", "c.txt"));
+        assert!(matches("log[0-9]", "log7"));
+        assert!(!matches("log[!0-9]", "log7"));
+        assert!(matches("log[^0-9]", "logx"));
+        assert!(matches("[]]", "]"));
+        // An unclosed bracket is literal
+        assert!(matches("a[b", "a[b"));
+    }
+
+    #[test]
+    fn test_expand_sorts_and_skips_hidden_files() {
+        let dir = TempDir::new().unwrap();
+        for name in ["b.tmp", "a.tmp", ".hidden.tmp", "keep.txt"] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("sub/c.tmp"), "").unwrap();
+
+        let root = dir.path().to_str().unwrap();
+        assert_eq!(
+            expand(&format!("{}/*.tmp", root)),
+            [format!("{}/a.tmp", root), format!("{}/b.tmp", root)]
+        );
+        assert_eq!(
+            expand(&format!("{}/.*.tmp", root)),
+            [format!("{}/.hidden.tmp", root)]
+        );
+        assert_eq!(
+            expand(&format!("{}/s*/*.tmp", root)),
+            [format!("{}/sub/c.tmp", root)]
+        );
+        assert!(expand(&format!("{}/*.none", root)).is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use std::ffi::OsStr;
 use std::fs::File;
 use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
 use std::path::Path;
@@ -43,6 +44,52 @@ pub fn write_and_flush<W: Write>(mut writer: W, data: &[u8]) -> io::Result<()> {
     Ok(())
 }
 
+/// Returns the record separator for line-oriented tools: NUL with
+/// `-z`/`--zero-terminated`, newline otherwise.
+pub fn record_separator(zero_terminated: bool) -> u8 {
+    if zero_terminated {
+        b'\0'
+    } else {
+        b'\n'
+    }
+}
+
+/// Writes each variable as `NAME=value` followed by `separator`, the way
+/// `env` and `printenv` list the environment. Names and values that
+/// aren't valid UTF-8 are written lossily.
+pub fn write_environment<W, I, N, V>(writer: &mut W, vars: I, separator: u8) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = (N, V)>,
+    N: AsRef<OsStr>,
+    V: AsRef<OsStr>,
+{
+    for (name, value) in vars {
+        write!(
+            writer,
+            "{}={}",
+            name.as_ref().to_string_lossy(),
+            value.as_ref().to_string_lossy()
+        )?;
+        writer.write_all(&[separator])?;
+    }
+    Ok(())
+}
+
+/// Reads the next record terminated by `separator`, without the separator.
+/// A final record with no terminator is still returned; `None` means the
+/// input is exhausted.
+pub fn read_record<R: BufRead>(reader: &mut R, separator: u8) -> io::Result<Option<Vec<u8>>> {
+    let mut record = Vec::new();
+    if reader.read_until(separator, &mut record)? == 0 {
+        return Ok(None);
+    }
+    if record.last() == Some(&separator) {
+        record.pop();
+    }
+    Ok(Some(record))
+}
+
 /// Counts the number of lines in the given reader.
 pub fn count_lines<R: BufRead>(reader: R) -> io::Result<usize> {
     Ok(reader.lines().count())
@@ -61,6 +108,43 @@ mod tests {
         assert_eq!(result, data);
     }
 
+    #[test]
+    fn test_read_record_lines() {
+        let mut reader = Cursor::new("one\ntwo\nthree");
+        let sep = record_separator(false);
+        assert_eq!(read_record(&mut reader, sep).unwrap(), Some(b"one".to_vec()));
+        assert_eq!(read_record(&mut reader, sep).unwrap(), Some(b"two".to_vec()));
+        assert_eq!(read_record(&mut reader, sep).unwrap(), Some(b"three".to_vec()));
+        assert_eq!(read_record(&mut reader, sep).unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_record_zero_terminated_round_trip() {
+        let input = b"a file\nwith newline\0plain\0";
+        let sep = record_separator(true);
+        let mut reader = Cursor::new(&input[..]);
+        let mut output = Vec::new();
+
+        while let Some(record) = read_record(&mut reader, sep).unwrap() {
+            output.extend_from_slice(&record);
+            output.push(sep);
+        }
+
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_write_environment() {
+        let vars = [("A", "1"), ("B", "x y"), ("C", "")];
+        let mut output = Vec::new();
+        write_environment(&mut output, vars, record_separator(false)).unwrap();
+        assert_eq!(output, b"A=1\nB=x y\nC=\n");
+
+        let mut output = Vec::new();
+        write_environment(&mut output, vars, record_separator(true)).unwrap();
+        assert_eq!(output, b"A=1\0B=x y\0C=\0");
+    }
+
     #[test]
     fn test_count_lines() {
         let data = "line1\nline2\nline3\n";
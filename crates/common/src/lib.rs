@@ -1,5 +1,13 @@
 pub mod error;
+pub mod escape;
+pub mod exit;
+pub mod fs;
+pub mod glob;
 pub mod io;
+pub mod mode;
+pub mod size;
+pub mod tabs;
+pub mod time;
 
 #[cfg(feature = "color")]
 pub mod color;
@@ -0,0 +1,150 @@
+//! File mode parsing, in the octal and symbolic forms `chmod` takes.
+
+use crate::error::{CommonError, Result};
+
+/// Permission bits a mode can hold: the setuid, setgid and sticky bits
+/// and read, write and execute for user, group and others.
+const ALL_BITS: u32 = 0o7777;
+
+/// Works out the mode `spec` describes, e.g. `755` or `u=rwx,go=rx`.
+///
+/// An octal mode is taken as it is. A symbolic one is a comma-separated
+/// list of changes applied in turn to `base`, each made of the classes
+/// it affects (`u`, `g`, `o`, or `a`), an operator (`+`, `-`, or `=`), and
+/// the permissions (`r`, `w`, `x`, `X`, `s`, `t`). With no classes given,
+/// the bits in `umask` are left out, as with `chmod`.
+pub fn parse_mode(spec: &str, base: u32, umask: u32) -> Result<u32> {
+    let invalid = || CommonError::InvalidArgument(format!("invalid mode '{}'", spec));
+
+    if !spec.is_empty() && spec.chars().all(|c| c.is_digit(8)) {
+        return u32::from_str_radix(spec, 8)
+            .ok()
+            .filter(|&mode| mode <= ALL_BITS)
+            .ok_or_else(invalid);
+    }
+
+    let mut mode = base & ALL_BITS;
+    for clause in spec.split(',') {
+        let mut chars = clause.chars().peekable();
+
+        let mut classes = 0;
+        while let Some(class) = chars.peek().and_then(|&c| class_bits(c)) {
+            classes |= class;
+            chars.next();
+        }
+        let (affected, allowed) = if classes == 0 {
+            (ALL_BITS, ALL_BITS & !umask)
+        } else {
+            (classes, classes)
+        };
+
+        // Each clause needs at least one operator
+        if chars.peek().is_none() {
+            return Err(invalid());
+        }
+        while let Some(operator) = chars.next() {
+            let mut permissions = 0;
+            while let Some(&c) = chars.peek().filter(|&&c| !matches!(c, '+' | '-' | '=')) {
+                permissions |= match c {
+                    'r' => 0o444,
+                    'w' => 0o222,
+                    'x' => 0o111,
+                    // Execute only if someone can already, as for a directory
+                    'X' if mode & 0o111 != 0 => 0o111,
+                    'X' => 0,
+                    's' => 0o6000,
+                    't' => 0o1000,
+                    _ => return Err(invalid()),
+                };
+                chars.next();
+            }
+
+            let bits = permissions & allowed;
+            match operator {
+                '+' => mode |= bits,
+                '-' => mode &= !bits,
+                '=' => mode = (mode & !affected) | bits,
+                _ => return Err(invalid()),
+            }
+        }
+    }
+
+    Ok(mode)
+}
+
+/// The bits that belong to a class letter, special bits included.
+fn class_bits(class: char) -> Option<u32> {
+    match class {
+        'u' => Some(0o4700),
+        'g' => Some(0o2070),
+        'o' => Some(0o1007),
+        'a' => Some(ALL_BITS),
+        _ => None,
+    }
+}
+
+/// The process's file mode creation mask.
+#[cfg(unix)]
+// mode_t is only u16 on some platforms, such as macOS
+#[allow(clippy::useless_conversion)]
+pub fn umask() -> u32 {
+    // umask can only be read by setting it, so put it straight back
+    // SAFETY: umask has no failure modes and only swaps the mask
+    unsafe {
+        let mask = libc::umask(0);
+        libc::umask(mask);
+        u32::from(mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_octal_modes() {
+        assert_eq!(parse_mode("755", 0, 0).unwrap(), 0o755);
+        assert_eq!(parse_mode("0700", 0o777, 0).unwrap(), 0o700);
+        assert_eq!(parse_mode("1777", 0, 0).unwrap(), 0o1777);
+        assert!(parse_mode("17777", 0, 0).is_err());
+        assert!(parse_mode("789", 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_symbolic_set() {
+        assert_eq!(parse_mode("u=rwx,go=rx", 0, 0).unwrap(), 0o755);
+        assert_eq!(parse_mode("u=rw,g=r,o=", 0o777, 0).unwrap(), 0o640);
+        assert_eq!(parse_mode("a=r", 0o777, 0).unwrap(), 0o444);
+    }
+
+    #[test]
+    fn test_symbolic_add_and_remove() {
+        assert_eq!(parse_mode("go-w", 0o777, 0).unwrap(), 0o755);
+        assert_eq!(parse_mode("u+x", 0o644, 0).unwrap(), 0o744);
+        assert_eq!(parse_mode("o-rwx,g+w", 0o755, 0).unwrap(), 0o770);
+        assert_eq!(parse_mode("u+s,+t", 0o755, 0).unwrap(), 0o5755);
+        assert_eq!(parse_mode("u=rwx-w", 0, 0).unwrap(), 0o500);
+    }
+
+    #[test]
+    fn test_symbolic_without_classes_respects_umask() {
+        assert_eq!(parse_mode("+w", 0o555, 0o022).unwrap(), 0o755);
+        assert_eq!(parse_mode("=rx", 0o777, 0o022).unwrap(), 0o555);
+        assert_eq!(parse_mode("a+w", 0o555, 0o022).unwrap(), 0o777);
+    }
+
+    #[test]
+    fn test_symbolic_conditional_execute() {
+        assert_eq!(parse_mode("a+X", 0o644, 0).unwrap(), 0o644);
+        assert_eq!(parse_mode("a+X", 0o744, 0).unwrap(), 0o755);
+    }
+
+    #[test]
+    fn test_invalid_symbolic_modes() {
+        assert!(parse_mode("", 0, 0).is_err());
+        assert!(parse_mode("u", 0, 0).is_err());
+        assert!(parse_mode("u=q", 0, 0).is_err());
+        assert!(parse_mode("z+r", 0, 0).is_err());
+        assert!(parse_mode("u+r,", 0, 0).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use crate::error::{CommonError, Result};
+
+/// Parses a size such as `512`, `10K`, `4MiB`, or `1MB` into bytes.
+///
+/// Suffixes follow GNU conventions: `K`, `M`, `G`, `T`, `P`, `E` (optionally
+/// followed by `iB`) are powers of 1024, the same letters followed by `B`
+/// are powers of 1000, and a bare `b` means 512-byte blocks.
+pub fn parse_size(input: &str) -> Result<u64> {
+    let invalid = || CommonError::InvalidArgument(format!("invalid size '{}'", input));
+
+    let digits_end = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (digits, suffix) = input.split_at(digits_end);
+
+    if digits.is_empty() {
+        return Err(invalid());
+    }
+    let value: u64 = digits.parse().map_err(|_| invalid())?;
+
+    let multiplier = match suffix {
+        "" => 1,
+        "b" => 512,
+        _ => {
+            let mut chars = suffix.chars();
+            let exponent = chars
+                .next()
+                .and_then(|c| "KMGTPE".find(c.to_ascii_uppercase()))
+                .ok_or_else(invalid)? as u32
+                + 1;
+            let base: u64 = match chars.as_str() {
+                "" | "iB" => 1024,
+                "B" => 1000,
+                _ => return Err(invalid()),
+            };
+            base.checked_pow(exponent).ok_or_else(invalid)?
+        }
+    };
+
+    value.checked_mul(multiplier).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plain_number() {
+        assert_eq!(parse_size("0").unwrap(), 0);
+        assert_eq!(parse_size("1234").unwrap(), 1234);
+    }
+
+    #[test]
+    fn test_parse_binary_suffixes() {
+        assert_eq!(parse_size("1K").unwrap(), 1024);
+        assert_eq!(parse_size("1k").unwrap(), 1024);
+        assert_eq!(parse_size("2KiB").unwrap(), 2048);
+        assert_eq!(parse_size("3M").unwrap(), 3 * 1024 * 1024);
+        assert_eq!(parse_size("1G").unwrap(), 1 << 30);
+    }
+
+    #[test]
+    fn test_parse_decimal_suffixes() {
+        assert_eq!(parse_size("1KB").unwrap(), 1000);
+        assert_eq!(parse_size("5MB").unwrap(), 5_000_000);
+    }
+
+    #[test]
+    fn test_parse_blocks() {
+        assert_eq!(parse_size("2b").unwrap(), 1024);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse_size("").is_err());
+        assert!(parse_size("K").is_err());
+        assert!(parse_size("10X").is_err());
+        assert!(parse_size("10KX").is_err());
+        assert!(parse_size("-5").is_err());
+        assert!(parse_size("99999999999E").is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::error::{CommonError, Result};
+
+/// Tab stop positions used by `expand` and `unexpand`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TabStops {
+    /// A tab stop every N columns.
+    Every(usize),
+    /// Explicit, strictly increasing tab stop columns.
+    List(Vec<usize>),
+}
+
+impl TabStops {
+    /// Parses a `-t` argument: either a single width (`4`) or a
+    /// comma- or blank-separated list of columns (`4,8,12`).
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut stops = Vec::new();
+
+        for part in spec.split([',', ' ']).filter(|s| !s.is_empty()) {
+            let stop: usize = part
+                .parse()
+                .map_err(|_| CommonError::InvalidArgument(format!("tab size '{}'", part)))?;
+
+            if stop == 0 {
+                return Err(CommonError::InvalidArgument(
+                    "tab size cannot be 0".to_string(),
+                ));
+            }
+            if stops.last().is_some_and(|&last| stop <= last) {
+                return Err(CommonError::InvalidArgument(
+                    "tab sizes must be ascending".to_string(),
+                ));
+            }
+            stops.push(stop);
+        }
+
+        match stops.len() {
+            0 => Err(CommonError::InvalidArgument(format!("tab list '{}'", spec))),
+            1 => Ok(Self::Every(stops[0])),
+            _ => Ok(Self::List(stops)),
+        }
+    }
+
+    /// Returns the first tab stop after `column` (0-based), or `None` when
+    /// `column` is past the last explicit stop.
+    pub fn next_stop(&self, column: usize) -> Option<usize> {
+        match self {
+            Self::Every(width) => Some((column / width + 1) * width),
+            Self::List(stops) => stops.iter().copied().find(|&stop| stop > column),
+        }
+    }
+
+    /// Returns true if `column` falls exactly on a tab stop.
+    pub fn is_stop(&self, column: usize) -> bool {
+        match self {
+            Self::Every(width) => column > 0 && column.is_multiple_of(*width),
+            Self::List(stops) => stops.binary_search(&column).is_ok(),
+        }
+    }
+}
+
+impl Default for TabStops {
+    fn default() -> Self {
+        Self::Every(8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_width() {
+        assert_eq!(TabStops::parse("4").unwrap(), TabStops::Every(4));
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            TabStops::parse("2,6 10").unwrap(),
+            TabStops::List(vec![2, 6, 10])
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(TabStops::parse("0").is_err());
+        assert!(TabStops::parse("8,4").is_err());
+        assert!(TabStops::parse("abc").is_err());
+        assert!(TabStops::parse("").is_err());
+    }
+
+    #[test]
+    fn test_next_stop() {
+        let every = TabStops::Every(4);
+        assert_eq!(every.next_stop(0), Some(4));
+        assert_eq!(every.next_stop(4), Some(8));
+
+        let list = TabStops::List(vec![3, 7]);
+        assert_eq!(list.next_stop(0), Some(3));
+        assert_eq!(list.next_stop(5), Some(7));
+        assert_eq!(list.next_stop(7), None);
+    }
+}
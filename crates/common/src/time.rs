@@ -0,0 +1,392 @@
+//! Calendar arithmetic on Unix timestamps.
+//!
+//! The conversions use Howard Hinnant's proleptic Gregorian algorithms,
+//! which are exact for any day count and need no lookup tables.
+
+use crate::error::{CommonError, Result};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Half of an average Gregorian year, the age at which `ls -l` switches
+/// from showing the time of day to showing the year.
+const SIX_MONTHS: i64 = 31_556_952 / 2;
+
+/// Day names, starting from Sunday as [`weekday_from_days`] does. The
+/// first three letters of each are its abbreviation.
+pub const WEEKDAY_NAMES: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// Month names, January first. The first three letters of each are its
+/// abbreviation.
+pub const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Seconds since the Unix epoch, negative for times before it.
+pub fn unix_seconds(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    }
+}
+
+/// Looks up the local UTC offset (in seconds) and zone abbreviation in
+/// effect at `secs`.
+#[cfg(unix)]
+pub fn local_zone(secs: i64) -> (i64, String) {
+    let time = secs as libc::time_t;
+    // SAFETY: `tm` is plain data, and localtime_r only writes into it
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return (0, "UTC".to_string());
+    }
+
+    let zone = if tm.tm_zone.is_null() {
+        String::new()
+    } else {
+        // SAFETY: a non-null tm_zone points at a static NUL-terminated name
+        unsafe { std::ffi::CStr::from_ptr(tm.tm_zone) }
+            .to_string_lossy()
+            .into_owned()
+    };
+    (tm.tm_gmtoff as i64, zone)
+}
+
+#[cfg(not(unix))]
+pub fn local_zone(_secs: i64) -> (i64, String) {
+    (0, "UTC".to_string())
+}
+
+/// Converts days since 1970-01-01 into a `(year, month, day)` civil date.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Converts a civil date into days since 1970-01-01. The inverse of
+/// [`civil_from_days`].
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let shifted_month = i64::from((month + 9) % 12);
+    let day_of_year = (153 * shifted_month + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Day of the week for days since 1970-01-01, with Sunday as 0.
+pub fn weekday_from_days(days: i64) -> u32 {
+    // 1970-01-01 was a Thursday
+    (days + 4).rem_euclid(7) as u32
+}
+
+/// Returns whether `year` has a February 29th.
+pub fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Number of days in `month` (1-12) of `year`.
+pub fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Turns a local wall-clock time, counted in seconds from the epoch as
+/// though it were UTC, into a real Unix timestamp.
+pub fn from_local(naive: i64) -> i64 {
+    naive - local_zone(naive).0
+}
+
+/// Parses a date as `date -d` and `touch -d` take it into seconds since
+/// the epoch: `@SECONDS`, `now`, `YYYY-MM-DD`, or `YYYY-MM-DD HH:MM[:SS]`
+/// (with a space or `T`). Times without a trailing `Z` are taken as local
+/// time unless `utc` is set.
+pub fn parse_date(input: &str, utc: bool) -> Result<i64> {
+    let invalid = || CommonError::InvalidArgument(format!("invalid date '{}'", input));
+    let trimmed = input.trim();
+
+    if let Some(seconds) = trimmed.strip_prefix('@') {
+        return seconds.parse().map_err(|_| invalid());
+    }
+    if trimmed == "now" {
+        return Ok(unix_seconds(SystemTime::now()));
+    }
+
+    let (trimmed, utc) = match trimmed.strip_suffix('Z') {
+        Some(rest) => (rest, true),
+        None => (trimmed, utc),
+    };
+    let (date, time) = match trimmed.split_once([' ', 'T']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (trimmed, None),
+    };
+
+    let date: Vec<&str> = date.split('-').collect();
+    let [year, month, day] = date[..] else {
+        return Err(invalid());
+    };
+    let year: i64 = year.parse().map_err(|_| invalid())?;
+    let month: u32 = month.parse().map_err(|_| invalid())?;
+    let day: u32 = day.parse().map_err(|_| invalid())?;
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return Err(invalid());
+    }
+
+    let seconds_of_day = match time {
+        Some(time) => {
+            let parts = time
+                .split(':')
+                .map(|part| part.parse::<i64>().map_err(|_| invalid()))
+                .collect::<Result<Vec<_>>>()?;
+            let (hour, minute, second) = match parts[..] {
+                [hour, minute] => (hour, minute, 0),
+                [hour, minute, second] => (hour, minute, second),
+                _ => return Err(invalid()),
+            };
+            if !(0..24).contains(&hour) || !(0..60).contains(&minute) || !(0..60).contains(&second)
+            {
+                return Err(invalid());
+            }
+            hour * 3600 + minute * 60 + second
+        }
+        None => 0,
+    };
+
+    let naive = days_from_civil(year, month, day) * SECONDS_PER_DAY + seconds_of_day;
+    if utc {
+        Ok(naive)
+    } else {
+        Ok(from_local(naive))
+    }
+}
+
+/// Formats a Unix timestamp as `YYYY-MM-DD HH:MM` in UTC, the layout of
+/// `ls --time-style=long-iso`.
+pub fn format_iso(secs: u64) -> String {
+    let secs = secs as i64;
+    let (year, month, day) = civil_from_days(secs.div_euclid(SECONDS_PER_DAY));
+    let seconds_of_day = secs.rem_euclid(SECONDS_PER_DAY);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60
+    )
+}
+
+/// Formats a Unix timestamp, already shifted to the wanted time zone, as
+/// `YYYY-MM-DDTHH:MM:SS`, the layout of a `.trashinfo` deletion date.
+pub fn format_datetime(secs: i64) -> String {
+    let (year, month, day) = civil_from_days(secs.div_euclid(SECONDS_PER_DAY));
+    let seconds_of_day = secs.rem_euclid(SECONDS_PER_DAY);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+/// Formats `secs`, shifted by the UTC `offset`, the way `ls -l` does:
+/// `Mon DD HH:MM` for times in the six months up to `now`, and
+/// `Mon DD  YYYY` for older times or times in the future.
+pub fn format_listing(secs: i64, offset: i64, now: i64) -> String {
+    let local = secs + offset;
+    let (year, month, day) = civil_from_days(local.div_euclid(SECONDS_PER_DAY));
+    let seconds_of_day = local.rem_euclid(SECONDS_PER_DAY);
+    let month = &MONTH_NAMES[month as usize - 1][..3];
+
+    if secs <= now && now - secs < SIX_MONTHS {
+        format!(
+            "{} {:>2} {:02}:{:02}",
+            month,
+            day,
+            seconds_of_day / 3600,
+            seconds_of_day % 3600 / 60
+        )
+    } else {
+        format!("{} {:>2} {:>5}", month, day, year)
+    }
+}
+
+/// Describes an age in seconds the way a human would, e.g. `5 minutes ago`.
+///
+/// Months are counted as 30 days and years as 365.
+pub fn format_relative(secs_ago: u64) -> String {
+    const UNITS: [(u64, &str); 5] = [
+        (365 * 86_400, "year"),
+        (30 * 86_400, "month"),
+        (86_400, "day"),
+        (3600, "hour"),
+        (60, "minute"),
+    ];
+
+    for (size, unit) in UNITS {
+        let count = secs_ago / size;
+        if count > 0 {
+            let plural = if count == 1 { "" } else { "s" };
+            return format!("{} {}{} ago", count, unit, plural);
+        }
+    }
+
+    "just now".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_civil_from_days_known_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(19_723), (2024, 1, 1));
+    }
+
+    #[test]
+    fn test_days_from_civil_round_trips() {
+        for days in [-800_000, -1, 0, 59, 11_016, 19_782, 2_932_896] {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+
+    #[test]
+    fn test_weekday() {
+        assert_eq!(weekday_from_days(0), 4);
+        assert_eq!(weekday_from_days(days_from_civil(2024, 3, 3)), 0);
+        assert_eq!(WEEKDAY_NAMES[weekday_from_days(0) as usize], "Thursday");
+    }
+
+    #[test]
+    fn test_format_iso() {
+        assert_eq!(format_iso(0), "1970-01-01 00:00");
+        assert_eq!(format_iso(951_782_400), "2000-02-29 00:00");
+        assert_eq!(format_iso(1_709_214_310), "2024-02-29 13:45");
+    }
+
+    #[test]
+    fn test_format_datetime() {
+        assert_eq!(format_datetime(0), "1970-01-01T00:00:00");
+        assert_eq!(format_datetime(1_709_214_310), "2024-02-29T13:45:10");
+        assert_eq!(format_datetime(-1), "1969-12-31T23:59:59");
+    }
+
+    #[test]
+    fn test_format_listing_recent() {
+        let now = 1_709_214_310; // 2024-02-29 13:45:10 UTC
+        assert_eq!(format_listing(now, 0, now), "Feb 29 13:45");
+        assert_eq!(format_listing(now - 86_400 * 30, 0, now), "Jan 30 13:45");
+        // One hour ahead of UTC moves the time and, here, the date
+        assert_eq!(format_listing(now - 3600 * 14, 3600, now), "Feb 29 00:45");
+        assert_eq!(format_listing(now - 3600 * 14, 0, now), "Feb 28 23:45");
+    }
+
+    #[test]
+    fn test_format_listing_old_or_future() {
+        let now = 1_709_214_310;
+        assert_eq!(format_listing(0, 0, now), "Jan  1  1970");
+        assert_eq!(format_listing(951_782_400, 0, now), "Feb 29  2000");
+        assert_eq!(format_listing(now - SIX_MONTHS + 1, 0, now), "Aug 30 22:50");
+        assert_eq!(format_listing(now - SIX_MONTHS, 0, now), "Aug 30  2023");
+        assert_eq!(format_listing(now + 60, 0, now), "Feb 29  2024");
+    }
+
+    #[test]
+    fn test_unix_seconds() {
+        assert_eq!(unix_seconds(UNIX_EPOCH), 0);
+        assert_eq!(
+            unix_seconds(UNIX_EPOCH - std::time::Duration::from_secs(90)),
+            -90
+        );
+    }
+
+    #[test]
+    fn test_format_relative() {
+        assert_eq!(format_relative(0), "just now");
+        assert_eq!(format_relative(59), "just now");
+        assert_eq!(format_relative(60), "1 minute ago");
+        assert_eq!(format_relative(2 * 3600 + 5), "2 hours ago");
+        assert_eq!(format_relative(29 * 86_400), "29 days ago");
+        assert_eq!(format_relative(45 * 86_400), "1 month ago");
+        assert_eq!(format_relative(3 * 365 * 86_400), "3 years ago");
+    }
+
+    #[test]
+    fn test_days_in_month() {
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert_eq!(days_in_month(2023, 4), 30);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("@1709214310", false).unwrap(), 1_709_214_310);
+        assert_eq!(
+            parse_date("2024-02-29 13:45:10", true).unwrap(),
+            1_709_214_310
+        );
+        assert_eq!(
+            parse_date("2024-02-29T13:45:10Z", false).unwrap(),
+            1_709_214_310
+        );
+        assert_eq!(parse_date("1970-01-02", true).unwrap(), SECONDS_PER_DAY);
+    }
+
+    #[test]
+    fn test_parse_date_invalid() {
+        assert!(parse_date("2023-02-29", true).is_err());
+        assert!(parse_date("2024-13-01", true).is_err());
+        assert!(parse_date("2024-01-01 24:00", true).is_err());
+        assert!(parse_date("yesterday", true).is_err());
+    }
+}
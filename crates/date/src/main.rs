@@ -0,0 +1,197 @@
+use anyhow::Result;
+use clap::Parser;
+use common::time::{
+    civil_from_days, days_from_civil, local_zone, parse_date, unix_seconds, weekday_from_days,
+    MONTH_NAMES, SECONDS_PER_DAY, WEEKDAY_NAMES,
+};
+use std::process::ExitCode;
+use std::time::SystemTime;
+
+const DEFAULT_FORMAT: &str = "%a %b %e %H:%M:%S %Z %Y";
+
+#[derive(Parser, Debug)]
+#[command(name = "date")]
+#[command(about = "Print the current or a given date and time", long_about = None)]
+#[command(version)]
+struct Args {
+    /// Print Coordinated Universal Time (UTC)
+    #[arg(short = 'u', long = "utc")]
+    utc: bool,
+
+    /// Display the time described by STRING instead of now
+    /// (@SECONDS, YYYY-MM-DD, or YYYY-MM-DD HH:MM[:SS], optionally ending in Z)
+    #[arg(short = 'd', long = "date", value_name = "STRING")]
+    date: Option<String>,
+
+    /// Output format, e.g. +%Y-%m-%d
+    #[arg(value_name = "+FORMAT")]
+    format: Option<String>,
+}
+
+fn main() -> ExitCode {
+    common::exit::report("date", run())
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse();
+
+    let format = match &args.format {
+        Some(format) => format
+            .strip_prefix('+')
+            .ok_or_else(|| anyhow::anyhow!("invalid date format '{}'", format))?,
+        None => DEFAULT_FORMAT,
+    };
+
+    let secs = match &args.date {
+        Some(input) => parse_date(input, args.utc)?,
+        None => now(),
+    };
+
+    let moment = if args.utc {
+        Moment::utc(secs)
+    } else {
+        Moment::local(secs)
+    };
+    println!("{}", format_moment(&moment, format));
+
+    Ok(())
+}
+
+fn now() -> i64 {
+    unix_seconds(SystemTime::now())
+}
+
+/// A point in time together with the UTC offset it should be shown in.
+struct Moment {
+    secs: i64,
+    offset: i64,
+    zone: String,
+}
+
+impl Moment {
+    fn utc(secs: i64) -> Self {
+        Self {
+            secs,
+            offset: 0,
+            zone: "UTC".to_string(),
+        }
+    }
+
+    fn local(secs: i64) -> Self {
+        let (offset, zone) = local_zone(secs);
+        Self { secs, offset, zone }
+    }
+}
+
+/// Expands the `%` specifiers in `format` for `moment`. Unknown
+/// specifiers are copied through unchanged.
+fn format_moment(moment: &Moment, format: &str) -> String {
+    let local = moment.secs + moment.offset;
+    let days = local.div_euclid(SECONDS_PER_DAY);
+    let seconds_of_day = local.rem_euclid(SECONDS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    let (hour, minute, second) = (
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60,
+    );
+    let weekday = WEEKDAY_NAMES[weekday_from_days(days) as usize];
+    let month_name = MONTH_NAMES[month as usize - 1];
+
+    let mut output = String::new();
+    let mut chars = format.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '%' {
+            output.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            Some('Y') => output.push_str(&year.to_string()),
+            Some('y') => output.push_str(&format!("{:02}", year.rem_euclid(100))),
+            Some('m') => output.push_str(&format!("{:02}", month)),
+            Some('d') => output.push_str(&format!("{:02}", day)),
+            Some('e') => output.push_str(&format!("{:>2}", day)),
+            Some('j') => {
+                let day_of_year = days - days_from_civil(year, 1, 1) + 1;
+                output.push_str(&format!("{:03}", day_of_year));
+            }
+            Some('H') => output.push_str(&format!("{:02}", hour)),
+            Some('M') => output.push_str(&format!("{:02}", minute)),
+            Some('S') => output.push_str(&format!("{:02}", second)),
+            Some('F') => output.push_str(&format!("{}-{:02}-{:02}", year, month, day)),
+            Some('T') => output.push_str(&format!("{:02}:{:02}:{:02}", hour, minute, second)),
+            Some('A') => output.push_str(weekday),
+            Some('a') => output.push_str(&weekday[..3]),
+            Some('B') => output.push_str(month_name),
+            Some('b') => output.push_str(&month_name[..3]),
+            Some('s') => output.push_str(&moment.secs.to_string()),
+            Some('Z') => output.push_str(&moment.zone),
+            Some('z') => {
+                let sign = if moment.offset < 0 { '-' } else { '+' };
+                let offset = moment.offset.abs();
+                output.push_str(&format!(
+                    "{}{:02}{:02}",
+                    sign,
+                    offset / 3600,
+                    offset % 3600 / 60
+                ));
+            }
+            Some('n') => output.push('\n'),
+            Some('t') => output.push('\t'),
+            Some('%') => output.push('%'),
+            Some(other) => {
+                output.push('%');
+                output.push(other);
+            }
+            None => output.push('%'),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-02-29 13:45:10 UTC, a Thursday
+    const LEAP_DAY: i64 = 1_709_214_310;
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(
+            format_moment(&Moment::utc(LEAP_DAY), "%Y-%m-%d"),
+            "2024-02-29"
+        );
+        assert_eq!(format_moment(&Moment::utc(0), "%Y-%m-%d"), "1970-01-01");
+    }
+
+    #[test]
+    fn test_format_names_and_time() {
+        let moment = Moment::utc(LEAP_DAY);
+        assert_eq!(
+            format_moment(&moment, "%A %B %H:%M:%S"),
+            "Thursday February 13:45:10"
+        );
+        assert_eq!(
+            format_moment(&moment, DEFAULT_FORMAT),
+            "Thu Feb 29 13:45:10 UTC 2024"
+        );
+        assert_eq!(format_moment(&moment, "%j %s %% %q"), "060 1709214310 % %q");
+    }
+
+    #[test]
+    fn test_format_with_offset() {
+        let moment = Moment {
+            secs: LEAP_DAY,
+            offset: -5 * 3600,
+            zone: "EST".to_string(),
+        };
+        assert_eq!(
+            format_moment(&moment, "%F %T %z %Z"),
+            "2024-02-29 08:45:10 -0500 EST"
+        );
+    }
+}
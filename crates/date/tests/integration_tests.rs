@@ -0,0 +1,29 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn test_date_utc_format() {
+    let mut cmd = Command::cargo_bin("date").unwrap();
+    cmd.arg("-u").arg("-d").arg("@86400").arg("+%Y-%m-%d %H:%M");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("1970-01-02 00:00\n"));
+}
+
+#[test]
+fn test_date_local_time_zone() {
+    let mut cmd = Command::cargo_bin("date").unwrap();
+    cmd.env("TZ", "UTC").arg("-d").arg("2024-02-29 13:45:10");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("Thu Feb 29 13:45:10 UTC 2024\n"));
+}
+
+#[test]
+fn test_date_invalid_input() {
+    let mut cmd = Command::cargo_bin("date").unwrap();
+    cmd.arg("-d").arg("not a date");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid date"));
+}
@@ -0,0 +1,276 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use common::exit::UsageError;
+use common::size::parse_size;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::process::ExitCode;
+
+const DEFAULT_BLOCK_SIZE: u64 = 512;
+
+#[derive(Parser, Debug)]
+#[command(name = "dd")]
+#[command(about = "Copy a file, block by block", long_about = None)]
+#[command(version)]
+struct Args {
+    /// Operands: if=FILE, of=FILE, bs=SIZE, count=N, skip=N, seek=N
+    #[arg(value_name = "OPERAND")]
+    operands: Vec<String>,
+}
+
+/// The copy described by the `NAME=VALUE` operands.
+#[derive(Debug, PartialEq)]
+struct Options {
+    /// Read from this file instead of stdin
+    input: Option<String>,
+    /// Write to this file instead of stdout
+    output: Option<String>,
+    /// Bytes per block, for reading, writing, `skip`, and `seek`
+    block_size: u64,
+    /// Copy at most this many blocks
+    count: Option<u64>,
+    /// Blocks to skip at the start of the input
+    skip: u64,
+    /// Blocks to skip at the start of the output
+    seek: u64,
+}
+
+impl Options {
+    fn parse(operands: &[String]) -> Result<Self> {
+        let mut options = Options {
+            input: None,
+            output: None,
+            block_size: DEFAULT_BLOCK_SIZE,
+            count: None,
+            skip: 0,
+            seek: 0,
+        };
+
+        for operand in operands {
+            let (name, value) = operand
+                .split_once('=')
+                .ok_or_else(|| UsageError::new(format!("unrecognized operand '{}'", operand)))?;
+
+            match name {
+                "if" => options.input = Some(value.to_string()),
+                "of" => options.output = Some(value.to_string()),
+                "bs" => {
+                    options.block_size = parse_size(value)?;
+                    if options.block_size == 0 {
+                        anyhow::bail!(UsageError::new(format!("invalid block size: '{}'", value)));
+                    }
+                }
+                "count" => options.count = Some(parse_size(value)?),
+                "skip" => options.skip = parse_size(value)?,
+                "seek" => options.seek = parse_size(value)?,
+                _ => anyhow::bail!(UsageError::new(format!(
+                    "unrecognized operand '{}'",
+                    operand
+                ))),
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Byte offset of `blocks` blocks, e.g. for `skip` or `seek`.
+    fn offset(&self, blocks: u64) -> Result<u64> {
+        blocks
+            .checked_mul(self.block_size)
+            .ok_or_else(|| UsageError::new("offset is too large").into())
+    }
+}
+
+/// Records and bytes moved, in the terms dd reports them: a full record
+/// is a whole block, a partial one is the short block at end of input.
+#[derive(Debug, Default, PartialEq)]
+struct Stats {
+    full: u64,
+    partial: u64,
+    bytes: u64,
+}
+
+fn main() -> ExitCode {
+    common::exit::report("dd", run())
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse();
+    let options = Options::parse(&args.operands)?;
+
+    let mut input: Box<dyn Read> = match &options.input {
+        Some(path) => {
+            Box::new(File::open(path).with_context(|| format!("failed to open '{}'", path))?)
+        }
+        None => Box::new(io::stdin().lock()),
+    };
+
+    let mut output: Box<dyn Write> = match &options.output {
+        Some(path) => Box::new(open_output(path, options.offset(options.seek)?)?),
+        None if options.seek > 0 => {
+            anyhow::bail!(UsageError::new("seek= needs an output file (of=)"))
+        }
+        None => Box::new(io::stdout().lock()),
+    };
+
+    let skip_bytes = options.offset(options.skip)?;
+    let skipped = io::copy(&mut input.by_ref().take(skip_bytes), &mut io::sink())
+        .context("failed to skip input")?;
+    if skipped < skip_bytes {
+        eprintln!("dd: cannot skip past end of input");
+    }
+
+    let stats = copy_blocks(&mut input, &mut output, options.block_size, options.count)?;
+    output.flush()?;
+
+    eprintln!("{}+{} records in", stats.full, stats.partial);
+    eprintln!("{}+{} records out", stats.full, stats.partial);
+    eprintln!("{} bytes copied", stats.bytes);
+
+    Ok(())
+}
+
+/// Opens `path` for writing, keeping the first `offset` bytes and
+/// dropping anything after them, like dd without `conv=notrunc`. Only
+/// regular files are truncated; devices such as `/dev/null` can't be.
+fn open_output(path: &str, offset: u64) -> Result<File> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .with_context(|| format!("failed to open '{}'", path))?;
+
+    let metadata = file
+        .metadata()
+        .with_context(|| format!("failed to open '{}'", path))?;
+    if metadata.is_file() {
+        file.set_len(offset)
+            .with_context(|| format!("failed to truncate '{}'", path))?;
+    }
+    if offset > 0 {
+        file.seek(SeekFrom::Start(offset))
+            .with_context(|| format!("failed to seek in '{}'", path))?;
+    }
+
+    Ok(file)
+}
+
+/// Copies up to `count` blocks of `block_size` bytes. Each block is filled
+/// completely before it is written, so only the last one can be partial.
+fn copy_blocks<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    block_size: u64,
+    count: Option<u64>,
+) -> Result<Stats> {
+    let mut block = vec![0u8; block_size as usize];
+    let mut stats = Stats::default();
+
+    while count.is_none_or(|count| stats.full + stats.partial < count) {
+        let filled = fill_block(reader, &mut block).context("failed to read input")?;
+        if filled == 0 {
+            break;
+        }
+
+        writer
+            .write_all(&block[..filled])
+            .context("failed to write output")?;
+        stats.bytes += filled as u64;
+
+        if filled == block.len() {
+            stats.full += 1;
+        } else {
+            stats.partial += 1;
+            break;
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Reads until `block` is full or the input ends, returning the number of
+/// bytes read.
+fn fill_block<R: Read>(reader: &mut R, block: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+
+    while filled < block.len() {
+        match reader.read(&mut block[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operands(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_operands() {
+        let options = Options::parse(&operands(&[
+            "if=in", "of=out", "bs=1K", "count=3", "skip=2", "seek=1",
+        ]))
+        .unwrap();
+        assert_eq!(
+            options,
+            Options {
+                input: Some("in".to_string()),
+                output: Some("out".to_string()),
+                block_size: 1024,
+                count: Some(3),
+                skip: 2,
+                seek: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_operands() {
+        assert!(Options::parse(&operands(&["bs=0"])).is_err());
+        assert!(Options::parse(&operands(&["bs=x"])).is_err());
+        assert!(Options::parse(&operands(&["nosuch=1"])).is_err());
+        assert!(Options::parse(&operands(&["if"])).is_err());
+    }
+
+    #[test]
+    fn test_copy_blocks_counts_records() {
+        let mut output = Vec::new();
+        let stats = copy_blocks(&mut &b"0123456789"[..], &mut output, 4, None).unwrap();
+        assert_eq!(output, b"0123456789");
+        assert_eq!(
+            stats,
+            Stats {
+                full: 2,
+                partial: 1,
+                bytes: 10
+            }
+        );
+    }
+
+    #[test]
+    fn test_copy_blocks_stops_at_count() {
+        let mut output = Vec::new();
+        let stats = copy_blocks(&mut &b"0123456789"[..], &mut output, 3, Some(2)).unwrap();
+        assert_eq!(output, b"012345");
+        assert_eq!(stats.full, 2);
+    }
+
+    #[test]
+    fn test_fill_block_joins_short_reads() {
+        // A chain of readers hands back one piece per read call
+        let mut reader = (&b"ab"[..]).chain(&b"cd"[..]).chain(&b"e"[..]);
+        let mut block = [0u8; 4];
+        assert_eq!(fill_block(&mut reader, &mut block).unwrap(), 4);
+        assert_eq!(&block, b"abcd");
+        assert_eq!(fill_block(&mut reader, &mut block).unwrap(), 1);
+    }
+}
@@ -0,0 +1,67 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_dd_copies_byte_range() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.bin");
+    let output = temp_dir.path().join("output.bin");
+    fs::write(&input, "0123456789abcdefghij").unwrap();
+
+    let mut cmd = Command::cargo_bin("dd").unwrap();
+    cmd.arg(format!("if={}", input.display()))
+        .arg(format!("of={}", output.display()))
+        .arg("bs=4")
+        .arg("skip=1")
+        .arg("count=3");
+    cmd.assert()
+        .success()
+        .stdout("")
+        .stderr("3+0 records in\n3+0 records out\n12 bytes copied\n");
+
+    assert_eq!(fs::read_to_string(&output).unwrap(), "456789abcdef");
+}
+
+#[test]
+fn test_dd_stdin_to_stdout_with_partial_record() {
+    let mut cmd = Command::cargo_bin("dd").unwrap();
+    cmd.arg("bs=4").write_stdin("hello world");
+    cmd.assert()
+        .success()
+        .stdout("hello world")
+        .stderr("2+1 records in\n2+1 records out\n11 bytes copied\n");
+}
+
+#[test]
+fn test_dd_seek_keeps_leading_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let output = temp_dir.path().join("output.txt");
+    fs::write(&output, "AAAABBBBCCCC").unwrap();
+
+    let mut cmd = Command::cargo_bin("dd").unwrap();
+    cmd.arg(format!("of={}", output.display()))
+        .arg("bs=4")
+        .arg("seek=1")
+        .write_stdin("xy");
+    cmd.assert().success();
+
+    assert_eq!(fs::read_to_string(&output).unwrap(), "AAAAxy");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dd_writes_to_a_device() {
+    let mut cmd = Command::cargo_bin("dd").unwrap();
+    cmd.arg("of=/dev/null").write_stdin("hello");
+    cmd.assert()
+        .success()
+        .stderr("0+1 records in\n0+1 records out\n5 bytes copied\n");
+}
+
+#[test]
+fn test_dd_rejects_unknown_operand() {
+    let mut cmd = Command::cargo_bin("dd").unwrap();
+    cmd.arg("bogus=1");
+    cmd.assert().code(2);
+}
@@ -1,6 +1,8 @@
 use anyhow::Result;
 use clap::Parser;
+use common::escape::{interpret_escapes_bytes, Escaped};
 use std::io::{self, Write};
+use std::process::ExitCode;
 
 #[derive(Parser, Debug)]
 #[command(name = "echo")]
@@ -24,16 +26,21 @@ struct Args {
     text: Vec<String>,
 }
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
+    common::exit::report("echo", run())
+}
+
+fn run() -> Result<()> {
     let args = Args::parse();
     
     let output = process_echo(&args)?;
-    print_output(&output, args.no_newline)?;
+    // \c drops the newline too, whatever -n says
+    print_output(&output.bytes, args.no_newline || output.stopped)?;
     
     Ok(())
 }
 
-fn process_echo(args: &Args) -> Result<String> {
+fn process_echo(args: &Args) -> Result<Escaped> {
     let text = args.text.join(" ");
     
     // -E flag explicitly disables escape interpretation
@@ -41,53 +48,21 @@ fn process_echo(args: &Args) -> Result<String> {
     let should_interpret_escapes = !args.no_escape && args.escape;
     
     if should_interpret_escapes {
-        Ok(interpret_escapes(&text))
+        // Bytes rather than text, as \xHH and \NNN can make any byte
+        Ok(interpret_escapes_bytes(&text))
     } else {
-        Ok(text)
+        Ok(Escaped {
+            bytes: text.into_bytes(),
+            stopped: false,
+        })
     }
 }
 
-fn interpret_escapes(text: &str) -> String {
-    let mut result = String::with_capacity(text.len());
-    let mut chars = text.chars().peekable();
-    
-    while let Some(ch) = chars.next() {
-        if ch == '\\' {
-            if let Some(&next) = chars.peek() {
-                chars.next(); // consume the next character
-                match next {
-                    'n' => result.push('\n'),
-                    't' => result.push('\t'),
-                    'r' => result.push('\r'),
-                    '\\' => result.push('\\'),
-                    'a' => result.push('\x07'), // alert (bell)
-                    'b' => result.push('\x08'), // backspace
-                    'f' => result.push('\x0C'), // form feed
-                    'v' => result.push('\x0B'), // vertical tab
-                    '0' => result.push('\0'),   // null
-                    _ => {
-                        // If not a recognized escape, keep the backslash and character
-                        result.push('\\');
-                        result.push(next);
-                    }
-                }
-            } else {
-                // Trailing backslash
-                result.push('\\');
-            }
-        } else {
-            result.push(ch);
-        }
-    }
-    
-    result
-}
-
-fn print_output(output: &str, no_newline: bool) -> Result<()> {
+fn print_output(output: &[u8], no_newline: bool) -> Result<()> {
     let stdout = io::stdout();
     let mut handle = stdout.lock();
     
-    handle.write_all(output.as_bytes())?;
+    handle.write_all(output)?;
     
     if !no_newline {
         handle.write_all(b"\n")?;
@@ -101,6 +76,7 @@ fn print_output(output: &str, no_newline: bool) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::escape::interpret_escapes;
 
     #[test]
     fn test_interpret_escapes_newline() {
@@ -132,6 +108,19 @@ mod tests {
         assert_eq!(interpret_escapes("hello\\"), "hello\\");
     }
 
+    #[test]
+    fn test_interpret_escapes_numeric() {
+        assert_eq!(interpret_escapes("\\x41"), "A");
+        assert_eq!(interpret_escapes("\\101"), "A");
+        assert_eq!(interpret_escapes("\\0101"), "A");
+    }
+
+    #[test]
+    fn test_interpret_escapes_unicode() {
+        assert_eq!(interpret_escapes("\\u00e9"), "\u{e9}");
+        assert_eq!(interpret_escapes("\\ud800"), "\\ud800");
+    }
+
     #[test]
     fn test_process_echo_no_escape() {
         let args = Args {
@@ -140,7 +129,7 @@ mod tests {
             no_escape: false,
             text: vec!["hello\\nworld".to_string()],
         };
-        assert_eq!(process_echo(&args).unwrap(), "hello\\nworld");
+        assert_eq!(process_echo(&args).unwrap().bytes, b"hello\\nworld");
     }
 
     #[test]
@@ -151,7 +140,20 @@ mod tests {
             no_escape: false,
             text: vec!["hello\\nworld".to_string()],
         };
-        assert_eq!(process_echo(&args).unwrap(), "hello\nworld");
+        assert_eq!(process_echo(&args).unwrap().bytes, b"hello\nworld");
+    }
+
+    #[test]
+    fn test_process_echo_stops_at_c() {
+        let args = Args {
+            no_newline: false,
+            escape: true,
+            no_escape: false,
+            text: vec!["a\\cb".to_string()],
+        };
+        let output = process_echo(&args).unwrap();
+        assert_eq!(output.bytes, b"a");
+        assert!(output.stopped);
     }
 
     #[test]
@@ -162,7 +164,7 @@ mod tests {
             no_escape: true,
             text: vec!["hello\\nworld".to_string()],
         };
-        assert_eq!(process_echo(&args).unwrap(), "hello\\nworld");
+        assert_eq!(process_echo(&args).unwrap().bytes, b"hello\\nworld");
     }
 }
 
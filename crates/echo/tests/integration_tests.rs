@@ -90,3 +90,26 @@ fn test_echo_backslash_escape() {
         .stdout(predicate::str::contains("hello\\world\n"));
 }
 
+
+#[test]
+fn test_echo_numeric_escapes() {
+    let mut cmd = Command::cargo_bin("echo").unwrap();
+    cmd.arg("-e").arg("\\x41\\0102\\103\\xff");
+    cmd.assert().success().stdout(predicate::eq(&b"ABC\xff\n"[..]));
+}
+
+#[test]
+fn test_echo_c_stops_output() {
+    let mut cmd = Command::cargo_bin("echo").unwrap();
+    cmd.arg("-e").arg("a\\cb").arg("more");
+    cmd.assert().success().stdout(predicate::eq("a"));
+}
+
+#[test]
+fn test_echo_unicode_escapes() {
+    let mut cmd = Command::cargo_bin("echo").unwrap();
+    cmd.arg("-e").arg("caf\\u00e9 \\U0001F600 \\ud800");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("café 😀 \\ud800\n"));
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use clap::Parser;
+use common::exit::UsageError;
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::process::{Command, ExitCode, ExitStatus};
+
+#[derive(Parser, Debug)]
+#[command(name = "env")]
+#[command(about = "Run a program in a modified environment", long_about = None)]
+#[command(version)]
+struct Args {
+    /// Start with an empty environment
+    #[arg(short = 'i', long = "ignore-environment")]
+    ignore_environment: bool,
+
+    /// Remove variable NAME from the environment
+    #[arg(short = 'u', long = "unset", value_name = "NAME")]
+    unset: Vec<String>,
+
+    /// End each output line with NUL, not newline
+    #[arg(short = '0', long = "null")]
+    null: bool,
+
+    /// NAME=VALUE assignments, followed by the command and its arguments
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    operands: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    common::exit::report("env", run())
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse();
+
+    let split = args
+        .operands
+        .iter()
+        .position(|operand| !operand.contains('='))
+        .unwrap_or(args.operands.len());
+    let (assignments, command) = args.operands.split_at(split);
+
+    let base = if args.ignore_environment {
+        Vec::new()
+    } else {
+        std::env::vars_os().collect()
+    };
+    let environment = build_environment(base, &args.unset, assignments);
+
+    let Some((program, program_args)) = command.split_first() else {
+        let separator = common::io::record_separator(args.null);
+        let mut stdout = common::io::stdout_writer();
+        let vars = environment.iter().map(|(name, value)| (name, value));
+        common::io::write_environment(&mut stdout, vars, separator)?;
+        stdout.flush()?;
+        return Ok(());
+    };
+
+    if args.null {
+        anyhow::bail!(UsageError::new("cannot specify --null (-0) with command"));
+    }
+
+    let status = Command::new(program)
+        .args(program_args)
+        .env_clear()
+        .envs(environment)
+        .status();
+
+    match status {
+        Ok(status) => std::process::exit(exit_code(status)),
+        Err(e) => {
+            eprintln!("env: '{}': {}", program, e);
+            // 127 for a missing program and 126 otherwise, as POSIX requires
+            let code = if e.kind() == io::ErrorKind::NotFound {
+                127
+            } else {
+                126
+            };
+            std::process::exit(code);
+        }
+    }
+}
+
+/// The status to exit with after running `status`'s program; a program
+/// killed by a signal maps to 128 + the signal number, as shells report it.
+#[cfg(unix)]
+fn exit_code(status: ExitStatus) -> i32 {
+    use std::os::unix::process::ExitStatusExt;
+
+    match (status.code(), status.signal()) {
+        (Some(code), _) => code,
+        (None, Some(signal)) => 128 + signal,
+        (None, None) => 1,
+    }
+}
+
+#[cfg(not(unix))]
+fn exit_code(status: ExitStatus) -> i32 {
+    status.code().unwrap_or(1)
+}
+
+/// Applies `-u` removals and then `NAME=VALUE` assignments to `base`,
+/// keeping the original order of variables that are left alone.
+fn build_environment(
+    mut environment: Vec<(OsString, OsString)>,
+    unset: &[String],
+    assignments: &[String],
+) -> Vec<(OsString, OsString)> {
+    environment.retain(|(name, _)| !unset.iter().any(|u| name == u.as_str()));
+
+    for assignment in assignments {
+        let (name, value) = assignment.split_once('=').unwrap_or((assignment, ""));
+        match environment
+            .iter_mut()
+            .find(|(existing, _)| existing == name)
+        {
+            Some(entry) => entry.1 = value.into(),
+            None => environment.push((name.into(), value.into())),
+        }
+    }
+
+    environment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(OsString, OsString)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (OsString::from(name), OsString::from(value)))
+            .collect()
+    }
+
+    #[test]
+    fn test_assignments_override_and_append() {
+        let environment = build_environment(
+            vars(&[("A", "1"), ("B", "2")]),
+            &[],
+            &["B=3".to_string(), "C=x=y".to_string()],
+        );
+        assert_eq!(environment, vars(&[("A", "1"), ("B", "3"), ("C", "x=y")]));
+    }
+
+    #[test]
+    fn test_unset_removes_variables() {
+        let environment =
+            build_environment(vars(&[("A", "1"), ("B", "2")]), &["A".to_string()], &[]);
+        assert_eq!(environment, vars(&[("B", "2")]));
+    }
+}
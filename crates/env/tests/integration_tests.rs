@@ -0,0 +1,41 @@
+use assert_cmd::cargo::cargo_bin;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn test_env_sets_variable_for_command() {
+    let mut cmd = Command::cargo_bin("env").unwrap();
+    cmd.arg("FOO=bar").arg(cargo_bin("printenv")).arg("FOO");
+    cmd.assert().success().stdout(predicate::eq("bar\n"));
+}
+
+#[test]
+fn test_env_ignore_environment() {
+    let mut cmd = Command::cargo_bin("env").unwrap();
+    cmd.env("ENV_TEST_KEEP", "1").arg("-i").arg("ONLY=1");
+    cmd.assert().success().stdout(predicate::eq("ONLY=1\n"));
+}
+
+#[test]
+fn test_env_unset() {
+    let mut cmd = Command::cargo_bin("env").unwrap();
+    cmd.env("ENV_TEST_GONE", "1").arg("-u").arg("ENV_TEST_GONE");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("ENV_TEST_GONE").not());
+}
+
+#[test]
+fn test_env_missing_command() {
+    let mut cmd = Command::cargo_bin("env").unwrap();
+    cmd.arg("definitely-not-a-real-command-xyz");
+    cmd.assert().code(127);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_env_reports_a_signal_as_128_plus_its_number() {
+    let mut cmd = Command::cargo_bin("env").unwrap();
+    cmd.arg("sh").arg("-c").arg("kill -TERM $$");
+    cmd.assert().code(143);
+}
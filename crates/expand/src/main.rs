@@ -0,0 +1,127 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use common::tabs::TabStops;
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+
+#[derive(Parser, Debug)]
+#[command(name = "expand")]
+#[command(about = "Convert tabs to spaces", long_about = None)]
+#[command(version)]
+struct Args {
+    /// Tab width N, or a comma-separated list of tab stop columns
+    #[arg(short = 't', long = "tabs", default_value = "8")]
+    tabs: String,
+
+    /// Files to process (use '-' for stdin)
+    #[arg(default_value = "-")]
+    files: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    common::exit::report("expand", run())
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse();
+    let stops = TabStops::parse(&args.tabs)?;
+    let mut stdout = common::io::stdout_writer();
+
+    for file in &args.files {
+        let reader = common::io::open_input(file)
+            .with_context(|| format!("Failed to open file: {}", file))?;
+        expand_reader(reader, &mut stdout, &stops)
+            .with_context(|| format!("Failed to process file: {}", file))?;
+    }
+
+    stdout.flush()?;
+    Ok(())
+}
+
+fn expand_reader<R: BufRead, W: Write>(
+    mut reader: R,
+    writer: &mut W,
+    stops: &TabStops,
+) -> io::Result<()> {
+    let mut line = Vec::new();
+
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        writer.write_all(&expand_line(&line, stops))?;
+        line.clear();
+    }
+
+    Ok(())
+}
+
+/// Replaces each tab with spaces up to the next tab stop.
+///
+/// Columns are counted per character rather than per byte, so multibyte
+/// UTF-8 sequences occupy a single column. Tabs past the last explicit
+/// stop become a single space.
+fn expand_line(line: &[u8], stops: &TabStops) -> Vec<u8> {
+    let mut output = Vec::with_capacity(line.len());
+    let mut column = 0;
+
+    for &byte in line {
+        match byte {
+            b'\t' => {
+                let stop = stops.next_stop(column).unwrap_or(column + 1);
+                output.resize(output.len() + (stop - column), b' ');
+                column = stop;
+            }
+            b'\n' => {
+                output.push(byte);
+                column = 0;
+            }
+            _ => {
+                output.push(byte);
+                if !is_continuation_byte(byte) {
+                    column += 1;
+                }
+            }
+        }
+    }
+
+    output
+}
+
+fn is_continuation_byte(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_width_4() {
+        let stops = TabStops::Every(4);
+        assert_eq!(expand_line(b"\ta\tbc\td", &stops), b"    a   bc  d");
+    }
+
+    #[test]
+    fn test_expand_default_width() {
+        let stops = TabStops::default();
+        assert_eq!(expand_line(b"ab\tc\n", &stops), b"ab      c\n");
+    }
+
+    #[test]
+    fn test_expand_multibyte_columns() {
+        let stops = TabStops::Every(4);
+        let result = expand_line("é\tx".as_bytes(), &stops);
+        assert_eq!(result, "é   x".as_bytes());
+    }
+
+    #[test]
+    fn test_expand_tab_list() {
+        let stops = TabStops::List(vec![2, 5]);
+        assert_eq!(expand_line(b"\ta\tb\tc", &stops), b"  a  b c");
+    }
+
+    #[test]
+    fn test_expand_reader_preserves_missing_newline() {
+        let mut output = Vec::new();
+        expand_reader(&b"a\tb\nc\td"[..], &mut output, &TabStops::Every(4)).unwrap();
+        assert_eq!(output, b"a   b\nc   d");
+    }
+}
@@ -0,0 +1,206 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use common::exit::UsageError;
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+
+const TAB_WIDTH: usize = 8;
+
+#[derive(Parser, Debug)]
+#[command(name = "fold")]
+#[command(about = "Wrap each input line to fit in a specified width", long_about = None)]
+#[command(version)]
+struct Args {
+    /// Use WIDTH columns instead of 80
+    #[arg(short = 'w', long = "width", default_value_t = 80)]
+    width: usize,
+
+    /// Break at spaces rather than in the middle of words
+    #[arg(short = 's', long = "spaces")]
+    spaces: bool,
+
+    /// Count bytes rather than columns
+    #[arg(short = 'b', long = "bytes")]
+    bytes: bool,
+
+    /// Files to process (use '-' for stdin)
+    #[arg(default_value = "-")]
+    files: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    common::exit::report("fold", run())
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse();
+
+    if args.width == 0 {
+        anyhow::bail!(UsageError::new("invalid number of columns: '0'"));
+    }
+
+    let options = FoldOptions {
+        width: args.width,
+        break_at_spaces: args.spaces,
+        count_bytes: args.bytes,
+    };
+    let mut stdout = common::io::stdout_writer();
+
+    for file in &args.files {
+        let reader = common::io::open_input(file)
+            .with_context(|| format!("Failed to open file: {}", file))?;
+        fold_reader(reader, &mut stdout, &options)
+            .with_context(|| format!("Failed to process file: {}", file))?;
+    }
+
+    stdout.flush()?;
+    Ok(())
+}
+
+struct FoldOptions {
+    width: usize,
+    break_at_spaces: bool,
+    count_bytes: bool,
+}
+
+fn fold_reader<R: BufRead, W: Write>(
+    mut reader: R,
+    writer: &mut W,
+    options: &FoldOptions,
+) -> io::Result<()> {
+    let mut line = Vec::new();
+
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        let has_newline = line.last() == Some(&b'\n');
+        if has_newline {
+            line.pop();
+        }
+
+        writer.write_all(&fold_line(&line, options))?;
+        if has_newline {
+            writer.write_all(b"\n")?;
+        }
+        line.clear();
+    }
+
+    Ok(())
+}
+
+/// Splits a single line (without its newline) into pieces no wider than
+/// `options.width`, joined by newlines.
+fn fold_line(line: &[u8], options: &FoldOptions) -> Vec<u8> {
+    let mut output = Vec::with_capacity(line.len());
+    let mut current = Vec::new();
+    let mut column = 0;
+
+    for &byte in line {
+        // Keep multibyte characters whole when counting columns
+        if !options.count_bytes && is_continuation_byte(byte) {
+            current.push(byte);
+            continue;
+        }
+
+        let mut next_column = advance_column(column, byte, options.count_bytes);
+
+        if next_column > options.width && !current.is_empty() {
+            let rest = if options.break_at_spaces {
+                current
+                    .iter()
+                    .rposition(|&b| b == b' ' || b == b'\t')
+                    .map(|pos| current.split_off(pos + 1))
+                    .unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+
+            output.append(&mut current);
+            output.push(b'\n');
+
+            current = rest;
+            column = current
+                .iter()
+                .filter(|&&b| options.count_bytes || !is_continuation_byte(b))
+                .fold(0, |col, &b| advance_column(col, b, options.count_bytes));
+            next_column = advance_column(column, byte, options.count_bytes);
+        }
+
+        current.push(byte);
+        column = next_column;
+    }
+
+    output.append(&mut current);
+    output
+}
+
+fn advance_column(column: usize, byte: u8, count_bytes: bool) -> usize {
+    if count_bytes {
+        return column + 1;
+    }
+
+    match byte {
+        b'\t' => column + TAB_WIDTH - column % TAB_WIDTH,
+        b'\x08' => column.saturating_sub(1),
+        b'\r' => 0,
+        _ => column + 1,
+    }
+}
+
+fn is_continuation_byte(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(width: usize, break_at_spaces: bool, count_bytes: bool) -> FoldOptions {
+        FoldOptions {
+            width,
+            break_at_spaces,
+            count_bytes,
+        }
+    }
+
+    #[test]
+    fn test_fold_width_10() {
+        let result = fold_line(b"hello world again", &options(10, false, false));
+        assert_eq!(result, b"hello worl\nd again");
+    }
+
+    #[test]
+    fn test_fold_width_10_at_spaces() {
+        let result = fold_line(b"hello world again", &options(10, true, false));
+        assert_eq!(result, b"hello \nworld \nagain");
+    }
+
+    #[test]
+    fn test_fold_spaces_without_blank_falls_back() {
+        let result = fold_line(b"abcdefghijkl", &options(5, true, false));
+        assert_eq!(result, b"abcde\nfghij\nkl");
+    }
+
+    #[test]
+    fn test_fold_tab_columns() {
+        let result = fold_line(b"ab\tcd", &options(8, false, false));
+        assert_eq!(result, b"ab\t\ncd");
+    }
+
+    #[test]
+    fn test_fold_bytes_counts_tab_as_one() {
+        let result = fold_line(b"ab\tcd", &options(8, false, true));
+        assert_eq!(result, b"ab\tcd");
+    }
+
+    #[test]
+    fn test_fold_multibyte_kept_whole() {
+        let result = fold_line("ééé".as_bytes(), &options(2, false, false));
+        assert_eq!(result, "éé\né".as_bytes());
+    }
+
+    #[test]
+    fn test_fold_reader_keeps_newlines() {
+        let mut output = Vec::new();
+        fold_reader(&b"abcdef\nxy"[..], &mut output, &options(4, false, false)).unwrap();
+        assert_eq!(output, b"abcd\nef\nxy");
+    }
+}
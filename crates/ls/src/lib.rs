@@ -0,0 +1,1013 @@
+//! Directory listing shared by the `ls` binary and the `cli-shell`
+//! built-in, so both behave the same.
+
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+use common::time::{format_listing, local_zone, unix_seconds};
+use std::cmp::Ordering;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+
+#[cfg(unix)]
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+#[derive(Parser, Debug)]
+#[command(name = "ls")]
+#[command(about = "List directory contents", long_about = None)]
+#[command(version)]
+#[command(disable_help_flag = true)]
+pub struct Args {
+    /// Directories or files to list
+    #[arg(default_value = ".")]
+    paths: Vec<String>,
+
+    /// Use long listing format
+    #[arg(short = 'l', long)]
+    long: bool,
+
+    /// Like -l, but list numeric user and group IDs
+    #[arg(short = 'n', long = "numeric-uid-gid")]
+    numeric_ids: bool,
+
+    /// Show hidden files (starting with .)
+    #[arg(short = 'a', long = "all")]
+    all: bool,
+
+    /// Human-readable sizes (1K, 234M, 2G)
+    #[arg(short = 'h', long = "human-readable")]
+    human_readable: bool,
+
+    /// Sort by modification time
+    #[arg(short = 't', long)]
+    time: bool,
+
+    /// Show and sort by the last status change time instead of modification time
+    #[arg(short = 'c', overrides_with = "access_time")]
+    change_time: bool,
+
+    /// Show and sort by the last access time instead of modification time
+    #[arg(short = 'u', overrides_with = "change_time")]
+    access_time: bool,
+
+    /// Reverse sort order
+    #[arg(short = 'r', long = "reverse")]
+    reverse: bool,
+
+    /// List directories before files, each group sorted as usual
+    #[arg(long = "group-directories-first")]
+    group_directories_first: bool,
+
+    /// List subdirectories recursively
+    #[arg(short = 'R', long = "recursive")]
+    recursive: bool,
+
+    /// List directories themselves, not their contents
+    #[arg(short = 'd', long = "directory")]
+    directory: bool,
+
+    /// Append an indicator to names: / for directories, * for executables, @ for symlinks
+    #[arg(short = 'F', long = "classify")]
+    classify: bool,
+
+    /// Append / to directory names
+    #[arg(short = 'p')]
+    slash_directories: bool,
+
+    /// List one entry per line
+    #[arg(short = '1', conflicts_with = "columns")]
+    one_per_line: bool,
+
+    /// List entries in columns, even when not writing to a terminal
+    #[arg(short = 'C')]
+    columns: bool,
+
+    /// Print the SELinux security context of each file
+    #[arg(short = 'Z', long = "context")]
+    context: bool,
+
+    /// Link file names to their file:// URI (always, auto, never)
+    #[arg(
+        long,
+        value_name = "WHEN",
+        value_enum,
+        num_args = 0..=1,
+        default_value = "never",
+        default_missing_value = "always",
+        require_equals = true
+    )]
+    hyperlink: When,
+
+    /// Color names by file type (always, auto, never); needs the `color` feature
+    #[arg(
+        long,
+        value_name = "WHEN",
+        value_enum,
+        num_args = 0..=1,
+        default_value = "auto",
+        default_missing_value = "always",
+        require_equals = true
+    )]
+    color: When,
+
+    /// Print help (-h is taken by --human-readable)
+    #[arg(long, action = clap::ArgAction::Help)]
+    help: Option<bool>,
+
+    /// Whether the listing is going to a terminal, which the caller knows
+    /// and the arguments don't
+    #[arg(skip)]
+    terminal: bool,
+
+    /// When a caller that can't kill the listing needs it to stop
+    #[arg(skip)]
+    deadline: Option<Instant>,
+}
+
+impl Args {
+    /// Marks the output as going to a terminal or not. Short listings are
+    /// only packed into columns on a terminal, and `auto` for `--color` and
+    /// `--hyperlink` means on a terminal.
+    pub fn terminal(mut self, terminal: bool) -> Self {
+        self.terminal = terminal;
+        self
+    }
+    
+    /// Makes the listing fail once `deadline` passes, checked before each
+    /// directory is read and between its entries.
+    pub fn deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+    
+    fn check_deadline(&self) -> Result<()> {
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            anyhow::bail!("deadline passed");
+        }
+        Ok(())
+    }
+    
+    /// Whether to use the long format, which `-n` implies.
+    fn long_format(&self) -> bool {
+        self.long || self.numeric_ids
+    }
+}
+
+/// When to enable an output feature that only makes sense on a terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum When {
+    Always,
+    Auto,
+    Never,
+}
+
+impl When {
+    /// Whether the feature is on, given whether the output goes to a
+    /// terminal.
+    fn enabled(self, terminal: bool) -> bool {
+        match self {
+            When::Always => true,
+            When::Auto => terminal,
+            When::Never => false,
+        }
+    }
+}
+
+/// Lists every path in `args`, writing the output to `writer`.
+pub fn run<W: Write>(args: &Args, writer: &mut W) -> Result<()> {
+    if args.directory {
+        return list_operands(args, writer);
+    }
+    
+    // Whether a -R section has been printed, so the next one is spaced out
+    let mut listed = false;
+    
+    for path_str in &args.paths {
+        list_path(path_str, args, writer, &mut listed)?;
+    }
+    
+    Ok(())
+}
+
+fn list_path<W: Write>(
+    path_str: &str,
+    args: &Args,
+    writer: &mut W,
+    listed: &mut bool,
+) -> Result<()> {
+    let path = Path::new(path_str);
+    
+    if !exists(path) {
+        anyhow::bail!("cannot access '{}': No such file or directory", path_str);
+    }
+    
+    if !path.is_dir() {
+        let mut entry = FileEntry::from_path(path)?;
+        entry.load_attributes(args);
+        print_entries(&[entry], args, writer)?;
+    } else if args.recursive {
+        list_tree(path, args, writer, listed)?;
+    } else {
+        list_directory(path, args, writer)?;
+    }
+    
+    Ok(())
+}
+
+/// Lists each path argument as a single entry, directories included, so
+/// that `ls -d */` prints one line per directory.
+fn list_operands<W: Write>(args: &Args, writer: &mut W) -> Result<()> {
+    let mut entries = Vec::new();
+    
+    for path_str in &args.paths {
+        let path = Path::new(path_str);
+        if !exists(path) {
+            anyhow::bail!("cannot access '{}': No such file or directory", path_str);
+        }
+        
+        let mut entry = FileEntry::from_path(path)?;
+        // Show the path as it was given, like `sub/dir/` rather than `dir`
+        entry.name = path_str.clone();
+        entry.load_attributes(args);
+        entries.push(entry);
+    }
+    
+    sort_entries(&mut entries, args);
+    print_entries(&entries, args, writer)?;
+    
+    Ok(())
+}
+
+/// Whether `path` exists, counting symlinks whose target doesn't.
+fn exists(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok()
+}
+
+fn list_directory<W: Write>(path: &Path, args: &Args, writer: &mut W) -> Result<()> {
+    let entries = read_entries(path, args)?;
+    print_entries(&entries, args, writer)?;
+    
+    Ok(())
+}
+
+/// Lists `path` and then every directory below it, depth first, each
+/// under a `path:` header like GNU `ls -R`. Symlinked directories are
+/// listed but never entered, so links can't send the walk in circles. A
+/// directory that can't be read is reported on stderr and skipped.
+fn list_tree<W: Write>(path: &Path, args: &Args, writer: &mut W, listed: &mut bool) -> Result<()> {
+    args.check_deadline()?;
+    if *listed {
+        writeln!(writer)?;
+    }
+    *listed = true;
+    writeln!(writer, "{}:", path.display())?;
+    
+    let entries = match read_entries(path, args) {
+        Ok(entries) => entries,
+        Err(e) => {
+            // Running out of time ends the walk rather than skipping a directory
+            args.check_deadline()?;
+            writer.flush()?;
+            eprintln!("ls: cannot open directory '{}': {}", path.display(), e.root_cause());
+            return Ok(());
+        }
+    };
+    print_entries(&entries, args, writer)?;
+    
+    for entry in entries.iter().filter(|entry| entry.is_dir && !entry.is_symlink) {
+        list_tree(&entry.path, args, writer, listed)?;
+    }
+    
+    Ok(())
+}
+
+/// Reads the entries of the directory at `path`, filtered by `-a` and
+/// sorted for display.
+fn read_entries(path: &Path, args: &Args) -> Result<Vec<FileEntry>> {
+    args.check_deadline()?;
+    let mut entries = Vec::new();
+    
+    let dir_entries = fs::read_dir(path)
+        .with_context(|| format!("Failed to read directory: {}", path.display()))?;
+    
+    for entry_result in dir_entries {
+        args.check_deadline()?;
+        let entry = entry_result?;
+        let file_name = entry.file_name();
+        let file_name_str = file_name.to_string_lossy();
+        
+        // Skip hidden files unless -a is specified
+        if !args.all && file_name_str.starts_with('.') {
+            continue;
+        }
+        
+        let mut file_entry = FileEntry::from_dir_entry(&entry)?;
+        file_entry.load_attributes(args);
+        entries.push(file_entry);
+    }
+    
+    // Sort entries
+    sort_entries(&mut entries, args);
+    
+    Ok(entries)
+}
+
+struct FileEntry {
+    name: String,
+    path: PathBuf,
+    size: u64,
+    nlinks: u64,
+    modified: Option<SystemTime>,
+    accessed: Option<SystemTime>,
+    /// Last status change, which only Unix records
+    changed: Option<SystemTime>,
+    is_dir: bool,
+    is_symlink: bool,
+    /// Where a symlink points, as stored in the link; it may not exist
+    link_target: Option<PathBuf>,
+    /// User and group names (or IDs), loaded only for `-l`
+    owner: String,
+    group: String,
+    /// Security context, loaded only for `-Z`
+    context: Option<String>,
+    /// `@` or `+` after the permissions, loaded only for `-l`
+    attribute_marker: &'static str,
+    #[cfg(unix)]
+    permissions: u32,
+    #[cfg(unix)]
+    uid: u32,
+    #[cfg(unix)]
+    gid: u32,
+}
+
+impl FileEntry {
+    fn from_path(path: &Path) -> Result<Self> {
+        // Describe a symlink itself, not what it points to
+        let metadata = fs::symlink_metadata(path)?;
+        let name = path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string());
+        
+        Ok(Self::from_metadata(name, path.to_path_buf(), &metadata))
+    }
+    
+    fn from_dir_entry(entry: &fs::DirEntry) -> Result<Self> {
+        let metadata = entry.metadata()?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        
+        Ok(Self::from_metadata(name, entry.path(), &metadata))
+    }
+    
+    fn from_metadata(name: String, path: PathBuf, metadata: &fs::Metadata) -> Self {
+        // Only a symlink has a target worth reading
+        let link_target = if metadata.is_symlink() {
+            fs::read_link(&path).ok()
+        } else {
+            None
+        };
+        
+        Self {
+            name,
+            path,
+            size: metadata.len(),
+            nlinks: link_count(metadata),
+            modified: metadata.modified().ok(),
+            accessed: metadata.accessed().ok(),
+            changed: change_time(metadata),
+            is_dir: metadata.is_dir(),
+            is_symlink: metadata.is_symlink(),
+            link_target,
+            owner: String::new(),
+            group: String::new(),
+            context: None,
+            attribute_marker: "",
+            #[cfg(unix)]
+            permissions: metadata.permissions().mode(),
+            #[cfg(unix)]
+            uid: metadata.uid(),
+            #[cfg(unix)]
+            gid: metadata.gid(),
+        }
+    }
+    
+    /// The time `-l` shows and `-t` sorts by: the modification time, or
+    /// the change or access time with `-c` or `-u`.
+    fn timestamp(&self, args: &Args) -> Option<SystemTime> {
+        if args.change_time {
+            self.changed
+        } else if args.access_time {
+            self.accessed
+        } else {
+            self.modified
+        }
+    }
+    
+    /// Reads the extended attributes needed by the requested columns.
+    fn load_attributes(&mut self, args: &Args) {
+        if args.context {
+            self.context = Some(security_context(&self.path));
+        }
+        if args.long_format() {
+            self.owner = owner_column(self, args);
+            self.group = group_column(self, args);
+            self.attribute_marker = attribute_marker(&self.path);
+        }
+    }
+    
+    #[cfg(unix)]
+    fn permissions_string(&self) -> String {
+        let mode = self.permissions;
+        let file_type = if self.is_symlink { 'l' } else if self.is_dir { 'd' } else { '-' };
+        
+        format!(
+            "{}{}{}{}{}{}{}{}{}{}",
+            file_type,
+            if mode & 0o400 != 0 { 'r' } else { '-' },
+            if mode & 0o200 != 0 { 'w' } else { '-' },
+            if mode & 0o100 != 0 { 'x' } else { '-' },
+            if mode & 0o040 != 0 { 'r' } else { '-' },
+            if mode & 0o020 != 0 { 'w' } else { '-' },
+            if mode & 0o010 != 0 { 'x' } else { '-' },
+            if mode & 0o004 != 0 { 'r' } else { '-' },
+            if mode & 0o002 != 0 { 'w' } else { '-' },
+            if mode & 0o001 != 0 { 'x' } else { '-' },
+        )
+    }
+    
+    #[cfg(not(unix))]
+    fn permissions_string(&self) -> String {
+        let file_type = if self.is_symlink { 'l' } else if self.is_dir { 'd' } else { '-' };
+        format!("{}rw-rw-rw-", file_type)
+    }
+    
+    /// Whether this is a file anyone may execute.
+    #[cfg(unix)]
+    fn is_executable(&self) -> bool {
+        !self.is_dir && self.permissions & 0o111 != 0
+    }
+    
+    #[cfg(not(unix))]
+    fn is_executable(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(unix)]
+fn link_count(metadata: &fs::Metadata) -> u64 {
+    metadata.nlink()
+}
+
+#[cfg(not(unix))]
+fn link_count(_metadata: &fs::Metadata) -> u64 {
+    1
+}
+
+#[cfg(unix)]
+fn change_time(metadata: &fs::Metadata) -> Option<SystemTime> {
+    use std::time::Duration;
+    
+    // The seconds may be negative, but the nanoseconds always count forward
+    let seconds = Duration::from_secs(metadata.ctime().unsigned_abs());
+    let whole = if metadata.ctime() >= 0 {
+        SystemTime::UNIX_EPOCH.checked_add(seconds)
+    } else {
+        SystemTime::UNIX_EPOCH.checked_sub(seconds)
+    };
+    whole?.checked_add(Duration::from_nanos(metadata.ctime_nsec() as u64))
+}
+
+#[cfg(not(unix))]
+fn change_time(_metadata: &fs::Metadata) -> Option<SystemTime> {
+    None
+}
+
+/// The owner column: the user's name, or the numeric ID with `-n` or when
+/// the user has no name.
+#[cfg(unix)]
+fn owner_column(entry: &FileEntry, args: &Args) -> String {
+    let name = if args.numeric_ids { None } else { user_name(entry.uid) };
+    name.unwrap_or_else(|| entry.uid.to_string())
+}
+
+#[cfg(not(unix))]
+fn owner_column(_entry: &FileEntry, _args: &Args) -> String {
+    "-".to_string()
+}
+
+/// The group column, resolved like [`owner_column`].
+#[cfg(unix)]
+fn group_column(entry: &FileEntry, args: &Args) -> String {
+    let name = if args.numeric_ids { None } else { group_name(entry.gid) };
+    name.unwrap_or_else(|| entry.gid.to_string())
+}
+
+#[cfg(not(unix))]
+fn group_column(_entry: &FileEntry, _args: &Args) -> String {
+    "-".to_string()
+}
+
+/// Looks up the name of the user with ID `uid` in the password database.
+#[cfg(unix)]
+fn user_name(uid: u32) -> Option<String> {
+    let mut buffer = vec![0; 1024];
+    loop {
+        // SAFETY: `passwd` is plain data; getpwuid_r fills it in, storing
+        // the strings it points to in `buffer`, whose length it is given
+        let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut result = std::ptr::null_mut();
+        let status = unsafe {
+            libc::getpwuid_r(uid, &mut passwd, buffer.as_mut_ptr(), buffer.len(), &mut result)
+        };
+        
+        match status {
+            libc::ERANGE => buffer.resize(buffer.len() * 2, 0),
+            // SAFETY: on success pw_name points at a NUL-terminated name in `buffer`
+            0 if !result.is_null() => return Some(unsafe { owned_c_string(passwd.pw_name) }),
+            _ => return None,
+        }
+    }
+}
+
+/// Looks up the name of the group with ID `gid` in the group database.
+#[cfg(unix)]
+fn group_name(gid: u32) -> Option<String> {
+    let mut buffer = vec![0; 1024];
+    loop {
+        // SAFETY: as for getpwuid_r in `user_name`
+        let mut group: libc::group = unsafe { std::mem::zeroed() };
+        let mut result = std::ptr::null_mut();
+        let status = unsafe {
+            libc::getgrgid_r(gid, &mut group, buffer.as_mut_ptr(), buffer.len(), &mut result)
+        };
+        
+        match status {
+            libc::ERANGE => buffer.resize(buffer.len() * 2, 0),
+            // SAFETY: on success gr_name points at a NUL-terminated name in `buffer`
+            0 if !result.is_null() => return Some(unsafe { owned_c_string(group.gr_name) }),
+            _ => return None,
+        }
+    }
+}
+
+/// Copies a NUL-terminated C string, which must be valid, into a `String`.
+#[cfg(unix)]
+unsafe fn owned_c_string(name: *const libc::c_char) -> String {
+    std::ffi::CStr::from_ptr(name).to_string_lossy().into_owned()
+}
+
+/// Reads the SELinux label of `path`, or `?` when it has none.
+#[cfg(target_os = "linux")]
+fn security_context(path: &Path) -> String {
+    match xattr::get(path, "security.selinux") {
+        Ok(Some(label)) => String::from_utf8_lossy(&label)
+            .trim_end_matches('\0')
+            .to_string(),
+        _ => "?".to_string(),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn security_context(_path: &Path) -> String {
+    "?".to_string()
+}
+
+/// Returns `+` if `path` has a POSIX ACL, `@` if it has other extended
+/// attributes, and an empty string otherwise. SELinux labels are left to
+/// `-Z` so that they don't mark every file on SELinux systems.
+#[cfg(unix)]
+fn attribute_marker(path: &Path) -> &'static str {
+    if !xattr::SUPPORTED_PLATFORM {
+        return "";
+    }
+    let Ok(names) = xattr::list(path) else {
+        return "";
+    };
+    
+    let mut marker = "";
+    for name in names {
+        let name = name.to_string_lossy();
+        if name.starts_with("system.posix_acl_") {
+            return "+";
+        }
+        if name != "security.selinux" {
+            marker = "@";
+        }
+    }
+    marker
+}
+
+#[cfg(not(unix))]
+fn attribute_marker(_path: &Path) -> &'static str {
+    ""
+}
+
+fn sort_entries(entries: &mut [FileEntry], args: &Args) {
+    entries.sort_by(|a, b| {
+        // Directories stay first with -r; only the order within each group flips
+        let group = if args.group_directories_first {
+            b.is_dir.cmp(&a.is_dir)
+        } else {
+            Ordering::Equal
+        };
+        
+        let ord = if args.time {
+            b.timestamp(args).cmp(&a.timestamp(args)) // newer first
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        } else {
+            a.name.to_lowercase().cmp(&b.name.to_lowercase())
+        };
+        
+        group.then(if args.reverse { ord.reverse() } else { ord })
+    });
+}
+
+/// Widths of the padded columns in long format, measured across every
+/// entry that is printed together.
+#[derive(Debug, Default, PartialEq)]
+struct ColumnWidths {
+    marker: usize,
+    nlinks: usize,
+    owner: usize,
+    group: usize,
+    context: usize,
+    size: usize,
+}
+
+impl ColumnWidths {
+    fn measure(entries: &[FileEntry], args: &Args) -> Self {
+        entries.iter().fold(Self::default(), |widths, entry| Self {
+            marker: widths.marker.max(entry.attribute_marker.len()),
+            nlinks: widths.nlinks.max(entry.nlinks.to_string().len()),
+            owner: widths.owner.max(entry.owner.chars().count()),
+            group: widths.group.max(entry.group.chars().count()),
+            context: widths.context.max(entry.context.as_ref().map_or(0, String::len)),
+            size: widths.size.max(size_string(entry, args).len()),
+        })
+    }
+}
+
+fn print_entries<W: Write>(entries: &[FileEntry], args: &Args, writer: &mut W) -> io::Result<()> {
+    let widths = ColumnWidths::measure(entries, args);
+    
+    if use_grid(args) {
+        return print_grid(entries, args, &widths, writer);
+    }
+    
+    for entry in entries {
+        print_entry(entry, args, &widths, writer)?;
+    }
+    
+    Ok(())
+}
+
+/// Short listings are packed into columns on a terminal, or anywhere with
+/// `-C`. Piped output stays one per line so scripts can read it.
+fn use_grid(args: &Args) -> bool {
+    !args.long_format() && !args.one_per_line && (args.columns || args.terminal)
+}
+
+/// Width to fill with columns: `$COLUMNS` if set, else the terminal's
+/// width, else 80.
+fn line_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .filter(|&columns| columns > 0)
+        .or_else(|| terminal_size::terminal_size().map(|(width, _)| usize::from(width.0)))
+        .unwrap_or(80)
+}
+
+fn print_grid<W: Write>(
+    entries: &[FileEntry],
+    args: &Args,
+    widths: &ColumnWidths,
+    writer: &mut W,
+) -> io::Result<()> {
+    let cells: Vec<(String, usize)> = entries
+        .iter()
+        .map(|entry| short_cell(entry, args, widths))
+        .collect();
+    let cell_widths: Vec<usize> = cells.iter().map(|(_, width)| *width).collect();
+    let (rows, column_widths) = grid_layout(&cell_widths, line_width());
+    
+    for row in 0..rows {
+        let mut line = String::new();
+        for (column, column_width) in column_widths.iter().enumerate() {
+            let Some((text, width)) = cells.get(column * rows + row) else {
+                break;
+            };
+            line.push_str(text);
+            // Pad only between cells, never after the last one on a line
+            if (column + 1) * rows + row < cells.len() {
+                line.push_str(&" ".repeat(column_width - width + GRID_GAP));
+            }
+        }
+        writeln!(writer, "{}", line)?;
+    }
+    
+    Ok(())
+}
+
+/// Spaces between grid columns.
+const GRID_GAP: usize = 2;
+
+/// Chooses the fewest rows (and so the most columns) whose grid fits in
+/// `line_width`, filling each column top to bottom like GNU ls. Returns
+/// the row count and the width of each column.
+fn grid_layout(widths: &[usize], line_width: usize) -> (usize, Vec<usize>) {
+    if widths.is_empty() {
+        return (0, Vec::new());
+    }
+    
+    for rows in 1..widths.len() {
+        let column_widths: Vec<usize> = widths
+            .chunks(rows)
+            .map(|column| column.iter().copied().max().unwrap_or(0))
+            .collect();
+        let total = column_widths.iter().sum::<usize>() + GRID_GAP * (column_widths.len() - 1);
+        if total <= line_width {
+            return (rows, column_widths);
+        }
+    }
+    
+    // One entry per row, however wide
+    (widths.len(), vec![widths.iter().copied().max().unwrap_or(0)])
+}
+
+fn print_entry<W: Write>(
+    entry: &FileEntry,
+    args: &Args,
+    widths: &ColumnWidths,
+    writer: &mut W,
+) -> io::Result<()> {
+    if args.long_format() {
+        print_long_format(entry, args, widths, writer)
+    } else {
+        writeln!(writer, "{}", short_cell(entry, args, widths).0)
+    }
+}
+
+/// The text of an entry in a short listing, with its width on screen.
+/// The width leaves out escape sequences such as hyperlinks.
+fn short_cell(entry: &FileEntry, args: &Args, widths: &ColumnWidths) -> (String, usize) {
+    let name_width = entry.name.chars().count() + type_indicator(entry, args).len();
+    
+    match &entry.context {
+        Some(context) => (
+            format!(
+                "{:<context_width$} {}{}",
+                context,
+                display_name(entry, args),
+                type_indicator(entry, args),
+                context_width = widths.context,
+            ),
+            widths.context + 1 + name_width,
+        ),
+        None => (
+            display_name(entry, args) + type_indicator(entry, args),
+            name_width,
+        ),
+    }
+}
+
+fn display_name(entry: &FileEntry, args: &Args) -> String {
+    let name = colorize(entry, args.color.enabled(args.terminal));
+    
+    if args.hyperlink.enabled(args.terminal) {
+        osc8_link(&entry.path, &name)
+    } else {
+        name
+    }
+}
+
+/// The character `-F` appends to a name, outside any color or link: `@` for symlinks, `/` for
+/// directories, `*` for executables, and nothing for regular files. `-p`
+/// only marks directories.
+fn type_indicator(entry: &FileEntry, args: &Args) -> &'static str {
+    if args.classify {
+        if entry.is_symlink {
+            "@"
+        } else if entry.is_dir {
+            "/"
+        } else if entry.is_executable() {
+            "*"
+        } else {
+            ""
+        }
+    } else if args.slash_directories && entry.is_dir {
+        "/"
+    } else {
+        ""
+    }
+}
+
+/// Colors the name by file type when `enabled`: directories blue,
+/// symlinks cyan, and executables green.
+#[cfg(feature = "color")]
+fn colorize(entry: &FileEntry, enabled: bool) -> String {
+    use common::color::{paint, schemes};
+    
+    if !enabled {
+        return entry.name.clone();
+    }
+    
+    if entry.is_symlink {
+        paint(&schemes::symlink(&entry.name))
+    } else if entry.is_dir {
+        paint(&schemes::directory(&entry.name))
+    } else if entry.is_executable() {
+        paint(&schemes::executable(&entry.name))
+    } else {
+        entry.name.clone()
+    }
+}
+
+#[cfg(not(feature = "color"))]
+fn colorize(entry: &FileEntry, _enabled: bool) -> String {
+    entry.name.clone()
+}
+
+/// Wraps `display` in an OSC 8 escape sequence linking to the `file://`
+/// URI of `path`, which supporting terminals render as a clickable link.
+fn osc8_link(path: &Path, display: &str) -> String {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    format!(
+        "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\",
+        file_uri(&absolute),
+        display
+    )
+}
+
+fn file_uri(path: &Path) -> String {
+    let mut path = path.to_string_lossy().into_owned();
+    if cfg!(windows) {
+        path = format!("/{}", path.replace('\\', "/"));
+    }
+    
+    let mut uri = String::from("file://");
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char);
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+fn size_string(entry: &FileEntry, args: &Args) -> String {
+    if args.human_readable {
+        format_size_human(entry.size)
+    } else {
+        entry.size.to_string()
+    }
+}
+
+fn print_long_format<W: Write>(
+    entry: &FileEntry,
+    args: &Args,
+    widths: &ColumnWidths,
+    writer: &mut W,
+) -> io::Result<()> {
+    let permissions = entry.permissions_string();
+    let size = size_string(entry, args);
+    
+    let modified = entry.timestamp(args)
+        .map(|t| {
+            let secs = unix_seconds(t);
+            format_listing(secs, local_zone(secs).0, unix_seconds(SystemTime::now()))
+        })
+        .unwrap_or_else(|| "?".to_string());
+    
+    let context = entry.context
+        .as_ref()
+        .map(|c| format!("{:<width$} ", c, width = widths.context))
+        .unwrap_or_default();
+    
+    // A symlink shows where it points instead of the `@` indicator
+    let suffix = match &entry.link_target {
+        Some(target) => format!(" -> {}", target.display()),
+        None => type_indicator(entry, args).to_string(),
+    };
+    
+    writeln!(
+        writer,
+        "{}{:<marker_width$} {:>nlink_width$} {:<owner_width$} {:<group_width$} {}{:>size_width$} {} {}{}",
+        permissions,
+        entry.attribute_marker,
+        entry.nlinks,
+        entry.owner,
+        entry.group,
+        context,
+        size,
+        modified,
+        display_name(entry, args),
+        suffix,
+        marker_width = widths.marker,
+        nlink_width = widths.nlinks,
+        owner_width = widths.owner,
+        group_width = widths.group,
+        size_width = widths.size,
+    )
+}
+
+fn format_size_human(size: u64) -> String {
+    const UNITS: &[&str] = &["B", "K", "M", "G", "T"];
+    let mut size = size as f64;
+    let mut unit_idx = 0;
+    
+    while size >= 1024.0 && unit_idx < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit_idx += 1;
+    }
+    
+    if unit_idx == 0 {
+        format!("{}{}", size as u64, UNITS[unit_idx])
+    } else {
+        format!("{:.1}{}", size, UNITS[unit_idx])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_size_human() {
+        assert_eq!(format_size_human(0), "0B");
+        assert_eq!(format_size_human(1023), "1023B");
+        assert_eq!(format_size_human(1024), "1.0K");
+        assert_eq!(format_size_human(1536), "1.5K");
+        assert_eq!(format_size_human(1048576), "1.0M");
+        assert_eq!(format_size_human(1073741824), "1.0G");
+    }
+
+    #[test]
+    fn test_osc8_link() {
+        let link = osc8_link(Path::new("/tmp/notes.txt"), "notes.txt");
+        assert_eq!(
+            link,
+            "\x1b]8;;file:///tmp/notes.txt\x1b\\notes.txt\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_osc8_link_relative_and_escaped() {
+        let link = osc8_link(Path::new("my file.txt"), "my file.txt");
+        let cwd = std::env::current_dir().unwrap();
+        let expected_uri = file_uri(&cwd.join("my file.txt"));
+        
+        assert!(expected_uri.ends_with("/my%20file.txt"));
+        assert!(link.starts_with(&format!("\x1b]8;;{}\x1b\\", expected_uri)));
+        assert!(link.contains("\\my file.txt\x1b]8;;"));
+    }
+
+    #[test]
+    fn test_grid_layout_fills_columns_first() {
+        // Five names of width 4 fit three to a line in 20 columns:
+        // 4 + 2 + 4 + 2 + 4 = 16, while four columns would need 22
+        let (rows, columns) = grid_layout(&[4; 5], 20);
+        assert_eq!(rows, 2);
+        assert_eq!(columns, [4, 4, 4]);
+    }
+
+    #[test]
+    fn test_grid_layout_sizes_each_column() {
+        let (rows, columns) = grid_layout(&[1, 8, 3, 2], 20);
+        assert_eq!(rows, 1);
+        assert_eq!(columns, [1, 8, 3, 2]);
+        
+        let (rows, columns) = grid_layout(&[1, 8, 3, 2], 13);
+        assert_eq!(rows, 2);
+        assert_eq!(columns, [8, 3]);
+        
+        let (rows, columns) = grid_layout(&[1, 8, 3, 2], 12);
+        assert_eq!(rows, 3);
+        assert_eq!(columns, [8, 2]);
+    }
+
+    #[test]
+    fn test_grid_layout_falls_back_to_one_column() {
+        assert_eq!(grid_layout(&[30, 30], 40), (2, vec![30]));
+        assert_eq!(grid_layout(&[], 80), (0, vec![]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_user_and_group_lookup() {
+        assert_eq!(user_name(0).as_deref(), Some("root"));
+        assert!(group_name(0).is_some());
+        // IDs with no entry fall back to numbers in the listing
+        assert_eq!(user_name(u32::MAX - 1), None);
+    }
+
+    #[test]
+    fn test_format_size_human_large() {
+        let size = 2_500_000_000_u64; // ~2.3 GB
+        let result = format_size_human(size);
+        assert!(result.ends_with('G'));
+    }
+}
+
@@ -1,6 +1,6 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
-use std::fs::{self, File};
+use std::fs::File;
 use std::io::Write;
 use tempfile::TempDir;
 
@@ -157,3 +157,434 @@ fn test_ls_file_instead_of_directory() {
         .stdout(predicate::str::contains("single_file.txt"));
 }
 
+
+#[test]
+fn test_ls_long_format_aligns_columns() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut small = File::create(temp_dir.path().join("small.txt")).unwrap();
+    write!(small, "x").unwrap();
+    let mut large = File::create(temp_dir.path().join("large.txt")).unwrap();
+    write!(large, "{}", "x".repeat(123_456)).unwrap();
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("-l").arg(temp_dir.path());
+    
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+    
+    // The one-byte size is padded to the width of "123456", so the date
+    // and name columns start at the same offset on both lines
+    let small_line = lines.iter().find(|l| l.ends_with("small.txt")).unwrap();
+    let large_line = lines.iter().find(|l| l.ends_with("large.txt")).unwrap();
+    assert!(small_line.contains("      1 "));
+    assert!(large_line.contains(" 123456 "));
+    assert_eq!(small_line.len(), large_line.len());
+    assert_eq!(small_line.rfind("small.txt"), large_line.rfind("large.txt"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_ls_context_column() {
+    let temp_dir = TempDir::new().unwrap();
+    File::create(temp_dir.path().join("labelled.txt")).unwrap();
+    
+    // Without SELinux the label is "?", but the column is still printed
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("-Z").arg(temp_dir.path());
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let fields: Vec<&str> = stdout.trim_end().split(' ').collect();
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields[1], "labelled.txt");
+    assert!(!fields[0].is_empty());
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("-lZ").arg(temp_dir.path());
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(&format!(" {} ", fields[0])));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ls_long_format_marks_extended_attributes() {
+    let temp_dir = TempDir::new().unwrap();
+    let tagged = temp_dir.path().join("tagged.txt");
+    File::create(&tagged).unwrap();
+    File::create(temp_dir.path().join("plain.txt")).unwrap();
+    
+    // Skip on filesystems without user xattr support
+    if xattr::set(&tagged, "user.rustcli.test", b"1").is_err() {
+        return;
+    }
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("-l").arg(temp_dir.path());
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    
+    let tagged_line = stdout.lines().find(|l| l.ends_with("tagged.txt")).unwrap();
+    let plain_line = stdout.lines().find(|l| l.ends_with("plain.txt")).unwrap();
+    assert!(tagged_line.starts_with("-rw-r--r--@ "), "{}", tagged_line);
+    assert!(plain_line.starts_with("-rw-r--r--  "), "{}", plain_line);
+}
+
+#[test]
+fn test_ls_recursive_prints_headers_per_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    std::fs::create_dir_all(root.join("b_dir/nested")).unwrap();
+    std::fs::create_dir(root.join(".hidden")).unwrap();
+    File::create(root.join("a.txt")).unwrap();
+    File::create(root.join("b_dir/inner.txt")).unwrap();
+    File::create(root.join("b_dir/nested/deep.txt")).unwrap();
+    File::create(root.join(".hidden/secret.txt")).unwrap();
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.current_dir(root).arg("-R");
+    cmd.assert().success().stdout(
+        ".:\na.txt\nb_dir\n\n./b_dir:\ninner.txt\nnested\n\n./b_dir/nested:\ndeep.txt\n",
+    );
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.current_dir(root).arg("-Ra").arg("b_dir");
+    cmd.assert()
+        .success()
+        .stdout("b_dir:\ninner.txt\nnested\n\nb_dir/nested:\ndeep.txt\n");
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.current_dir(root).arg("-Ra");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("./.hidden:\nsecret.txt\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ls_recursive_does_not_follow_symlinked_directories() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    std::fs::create_dir(root.join("dir")).unwrap();
+    File::create(root.join("dir/file.txt")).unwrap();
+    // A link back up the tree would loop forever if followed
+    std::os::unix::fs::symlink(root, root.join("dir/loop")).unwrap();
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.current_dir(root).arg("-R").arg("dir");
+    cmd.assert()
+        .success()
+        .stdout("dir:\nfile.txt\nloop\n");
+}
+
+#[test]
+fn test_ls_directory_lists_the_directory_itself() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    std::fs::create_dir(root.join("beta")).unwrap();
+    std::fs::create_dir(root.join("alpha")).unwrap();
+    File::create(root.join("beta/inside.txt")).unwrap();
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.current_dir(root).arg("-d").arg("beta/").arg("alpha");
+    cmd.assert().success().stdout("alpha\nbeta/\n");
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.current_dir(root).arg("-ld").arg("beta");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("d"))
+        .stdout(predicate::str::ends_with(" beta\n"))
+        .stdout(predicate::str::contains("inside.txt").not());
+}
+
+#[test]
+fn test_ls_columns_fill_down_then_across() {
+    let temp_dir = TempDir::new().unwrap();
+    for name in ["aa", "bb", "cc", "dd", "ee"] {
+        File::create(temp_dir.path().join(name)).unwrap();
+    }
+    
+    // Three columns of two rows fit in 12 characters: "aa  cc  ee"
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.env("COLUMNS", "12").arg("-C").arg(temp_dir.path());
+    cmd.assert().success().stdout("aa  cc  ee\nbb  dd\n");
+    
+    // Piped output without -C stays one per line
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.env("COLUMNS", "12").arg(temp_dir.path());
+    cmd.assert().success().stdout("aa\nbb\ncc\ndd\nee\n");
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn test_ls_color_by_file_type() {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::create_dir(temp_dir.path().join("dir")).unwrap();
+    File::create(temp_dir.path().join("plain.txt")).unwrap();
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("--color=always").arg(temp_dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[1;34mdir\x1b[0m\n"))
+        .stdout(predicate::str::contains("\nplain.txt\n"));
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("--color=never").arg(temp_dir.path());
+    cmd.assert().success().stdout("dir\nplain.txt\n");
+    
+    // Escape codes don't count towards the column widths
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.env("COLUMNS", "20")
+        .arg("-C")
+        .arg("--color=always")
+        .arg(temp_dir.path());
+    cmd.assert()
+        .success()
+        .stdout("\x1b[1;34mdir\x1b[0m  plain.txt\n");
+}
+
+#[cfg(all(unix, feature = "color"))]
+#[test]
+fn test_ls_color_executables() {
+    use std::os::unix::fs::PermissionsExt;
+    
+    let temp_dir = TempDir::new().unwrap();
+    let script = temp_dir.path().join("run.sh");
+    File::create(&script).unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("--color=always").arg(temp_dir.path());
+    cmd.assert()
+        .success()
+        .stdout("\x1b[1;32mrun.sh\x1b[0m\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ls_classify_marks_file_types() {
+    use std::os::unix::fs::PermissionsExt;
+    
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    std::fs::create_dir(root.join("dir")).unwrap();
+    File::create(root.join("plain.txt")).unwrap();
+    File::create(root.join("run.sh")).unwrap();
+    std::fs::set_permissions(root.join("run.sh"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::os::unix::fs::symlink("plain.txt", root.join("link")).unwrap();
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("-F").arg(root);
+    cmd.assert()
+        .success()
+        .stdout("dir/\nlink@\nplain.txt\nrun.sh*\n");
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("-p").arg(root);
+    cmd.assert()
+        .success()
+        .stdout("dir/\nlink\nplain.txt\nrun.sh\n");
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("-lF").arg(root);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(" dir/\n"))
+        .stdout(predicate::str::contains(" run.sh*\n"))
+        .stdout(predicate::str::contains(" plain.txt\n"));
+}
+
+#[test]
+fn test_ls_classify_counts_towards_column_widths() {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::create_dir(temp_dir.path().join("aa")).unwrap();
+    File::create(temp_dir.path().join("bb")).unwrap();
+    File::create(temp_dir.path().join("cc")).unwrap();
+    
+    // "aa/" makes the first column three wide
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.env("COLUMNS", "40").arg("-CF").arg(temp_dir.path());
+    cmd.assert().success().stdout("aa/  bb  cc\n");
+}
+
+#[test]
+fn test_ls_group_directories_first() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    for name in ["b_dir", "d_dir"] {
+        std::fs::create_dir(root.join(name)).unwrap();
+    }
+    for name in ["a.txt", "c.txt"] {
+        File::create(root.join(name)).unwrap();
+    }
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("--group-directories-first").arg(root);
+    cmd.assert().success().stdout("b_dir\nd_dir\na.txt\nc.txt\n");
+    
+    // -r reverses each group but keeps directories ahead of files
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("--group-directories-first").arg("-r").arg(root);
+    cmd.assert().success().stdout("d_dir\nb_dir\nc.txt\na.txt\n");
+}
+
+#[test]
+fn test_ls_group_directories_first_with_time() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    std::fs::create_dir(root.join("old_dir")).unwrap();
+    std::fs::create_dir(root.join("new_dir")).unwrap();
+    File::create(root.join("old.txt")).unwrap();
+    File::create(root.join("new.txt")).unwrap();
+    
+    // The files are newer than both directories
+    let times = [
+        ("old_dir", 100),
+        ("new_dir", 200),
+        ("old.txt", 300),
+        ("new.txt", 400),
+    ];
+    for (name, seconds) in times {
+        let time = filetime::FileTime::from_unix_time(1_700_000_000 + seconds, 0);
+        filetime::set_file_mtime(root.join(name), time).unwrap();
+    }
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("--group-directories-first").arg("-t").arg(root);
+    cmd.assert().success().stdout("new_dir\nold_dir\nnew.txt\nold.txt\n");
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("--group-directories-first").arg("-tr").arg(root);
+    cmd.assert().success().stdout("old_dir\nnew_dir\nold.txt\nnew.txt\n");
+}
+
+#[test]
+fn test_ls_sort_by_time_breaks_ties_by_name() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let time = filetime::FileTime::from_unix_time(1_700_000_000, 0);
+    for name in ["c.txt", "a.txt", "b.txt"] {
+        File::create(root.join(name)).unwrap();
+        filetime::set_file_mtime(root.join(name), time).unwrap();
+    }
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("-t").arg(root);
+    cmd.assert().success().stdout("a.txt\nb.txt\nc.txt\n");
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("-tr").arg(root);
+    cmd.assert().success().stdout("c.txt\nb.txt\na.txt\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ls_long_format_shows_symlink_targets() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    File::create(root.join("target.txt")).unwrap();
+    std::os::unix::fs::symlink("target.txt", root.join("link")).unwrap();
+    std::os::unix::fs::symlink("nowhere", root.join("dangling")).unwrap();
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("-l").arg(root);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_match(r"(?m)^l.* link -> target\.txt$").unwrap())
+        .stdout(predicate::str::is_match(r"(?m)^l.* dangling -> nowhere$").unwrap());
+    
+    // A dangling link given by name is still listed, not reported missing
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.current_dir(root).arg("-lF").arg("dangling");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^l.* dangling -> nowhere\n$").unwrap());
+    
+    // Short listings show just the name
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg(root);
+    cmd.assert()
+        .success()
+        .stdout("dangling\nlink\ntarget.txt\n");
+}
+
+#[test]
+fn test_ls_access_time_sorts_and_displays() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    File::create(root.join("a.txt")).unwrap();
+    File::create(root.join("b.txt")).unwrap();
+    
+    // a.txt was modified last but b.txt was read last
+    let old = filetime::FileTime::from_unix_time(1_000_000_000, 0);
+    let new = filetime::FileTime::from_unix_time(1_100_000_000, 0);
+    filetime::set_file_times(root.join("a.txt"), old, new).unwrap();
+    filetime::set_file_times(root.join("b.txt"), new, old).unwrap();
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("-t").arg(root);
+    cmd.assert().success().stdout("a.txt\nb.txt\n");
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("-tu").arg(root);
+    cmd.assert().success().stdout("b.txt\na.txt\n");
+    
+    // -u without -t changes the time shown but not the order
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.env("TZ", "UTC").arg("-lu").arg(root);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(" Sep  9  2001 a.txt\n"))
+        .stdout(predicate::str::contains(" Nov  9  2004 b.txt\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ls_change_time_is_shown_with_c() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("file.txt");
+    File::create(&file).unwrap();
+    
+    // Backdating the mtime still bumps the change time to now
+    let old = filetime::FileTime::from_unix_time(1_000_000_000, 0);
+    filetime::set_file_mtime(&file, old).unwrap();
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.env("TZ", "UTC").arg("-l").arg(&file);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(" Sep  9  2001 "));
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.env("TZ", "UTC").arg("-lc").arg(&file);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("2001").not());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ls_numeric_ids_imply_long_format() {
+    use std::os::unix::fs::MetadataExt;
+    
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("owned.txt");
+    File::create(&file).unwrap();
+    let metadata = std::fs::metadata(&file).unwrap();
+    
+    let mut cmd = Command::cargo_bin("ls").unwrap();
+    cmd.arg("-n").arg(&file);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("-rw"))
+        .stdout(predicate::str::contains(format!(
+            " 1 {} {} 0 ",
+            metadata.uid(),
+            metadata.gid()
+        )));
+}
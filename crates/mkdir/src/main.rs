@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
 use clap::Parser;
 use std::fs;
+use std::io;
 use std::path::Path;
+use std::process::ExitCode;
 
 #[derive(Parser, Debug)]
 #[command(name = "mkdir")]
@@ -16,23 +18,51 @@ struct Args {
     #[arg(short = 'v', long = "verbose")]
     verbose: bool,
 
+    /// Set the mode of created directories, in octal (755) or symbolic
+    /// (u=rwx,go=rx) form; with -p, parents keep the default mode
+    #[arg(short = 'm', long = "mode", value_name = "MODE")]
+    mode: Option<String>,
+
     /// Directories to create
     #[arg(required = true)]
     directories: Vec<String>,
 }
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
+    common::exit::report("mkdir", run())
+}
+
+fn run() -> Result<()> {
     let args = Args::parse();
+    let mode = args.mode.as_deref().map(parse_mode).transpose()?.flatten();
     
     for dir in &args.directories {
-        create_directory(dir, args.parents, args.verbose)
+        create_directory(dir, args.parents, args.verbose, mode)
             .with_context(|| format!("Failed to create directory: {}", dir))?;
     }
     
     Ok(())
 }
 
-fn create_directory(path: &str, create_parents: bool, verbose: bool) -> Result<()> {
+/// Works out the mode for `-m`. Symbolic modes start from `a=rwx`, which
+/// the umask applies to, as for GNU mkdir.
+#[cfg(unix)]
+fn parse_mode(spec: &str) -> Result<Option<u32>> {
+    Ok(Some(common::mode::parse_mode(spec, 0o777, common::mode::umask())?))
+}
+
+#[cfg(not(unix))]
+fn parse_mode(_spec: &str) -> Result<Option<u32>> {
+    eprintln!("mkdir: warning: -m is not supported on this platform and is ignored");
+    Ok(None)
+}
+
+fn create_directory(
+    path: &str,
+    create_parents: bool,
+    verbose: bool,
+    mode: Option<u32>,
+) -> Result<()> {
     let path_obj = Path::new(path);
     
     // Check if directory already exists
@@ -45,9 +75,37 @@ fn create_directory(path: &str, create_parents: bool, verbose: bool) -> Result<(
     }
     
     if create_parents {
-        fs::create_dir_all(path_obj)?;
-    } else {
-        fs::create_dir(path_obj)?;
+        // One level at a time, top down, so each new parent can be reported
+        let mut missing: Vec<&Path> = path_obj
+            .ancestors()
+            .skip(1)
+            .take_while(|parent| !parent.as_os_str().is_empty() && !parent.exists())
+            .collect();
+        missing.reverse();
+        
+        for parent in missing {
+            match fs::create_dir(parent) {
+                Ok(()) => {
+                    if verbose {
+                        println!("created directory '{}'", parent.display());
+                    }
+                }
+                // Someone else made it in the meantime, which is fine
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists && parent.is_dir() => {}
+                Err(e) => {
+                    return Err(e)
+                        .with_context(|| format!("cannot create directory '{}'", parent.display()))
+                }
+            }
+        }
+    }
+    
+    fs::create_dir(path_obj)?;
+    
+    // Set afterwards, as the mode given to the system call would be cut
+    // down by the umask
+    if let Some(mode) = mode {
+        set_mode(path_obj, mode)?;
     }
     
     if verbose {
@@ -57,6 +115,18 @@ fn create_directory(path: &str, create_parents: bool, verbose: bool) -> Result<(
     Ok(())
 }
 
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+        .with_context(|| format!("cannot set permissions of '{}'", path.display()))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> Result<()> {
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -71,7 +141,7 @@ mod tests {
         // Clean up if exists
         let _ = fs::remove_dir(&test_dir);
         
-        let result = create_directory(test_dir.to_str().unwrap(), false, false);
+        let result = create_directory(test_dir.to_str().unwrap(), false, false, None);
         assert!(result.is_ok());
         assert!(test_dir.exists());
         
@@ -87,7 +157,7 @@ mod tests {
         // Clean up if exists
         let _ = fs::remove_dir_all(&test_dir);
         
-        let result = create_directory(test_dir.to_str().unwrap(), true, false);
+        let result = create_directory(test_dir.to_str().unwrap(), true, false, None);
         assert!(result.is_ok());
         assert!(test_dir.exists());
         
@@ -103,11 +173,26 @@ mod tests {
         // Create the directory first
         let _ = fs::create_dir(&test_dir);
         
-        let result = create_directory(test_dir.to_str().unwrap(), false, false);
+        let result = create_directory(test_dir.to_str().unwrap(), false, false, None);
         assert!(result.is_err());
         
         // Cleanup
         fs::remove_dir(&test_dir).unwrap();
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_mode_applies_only_to_final_directory() {
+        use std::os::unix::fs::PermissionsExt;
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let parent = temp_dir.path().join("parent");
+        let child = parent.join("child");
+        
+        create_directory(child.to_str().unwrap(), true, false, Some(0o700)).unwrap();
+        
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o7777;
+        assert_eq!(mode(&child), 0o700);
+        assert_eq!(mode(&parent), 0o777 & !common::mode::umask());
+    }
 }
 
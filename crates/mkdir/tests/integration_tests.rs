@@ -0,0 +1,60 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+#[cfg(unix)]
+fn mode(path: &std::path::Path) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).unwrap().permissions().mode() & 0o7777
+}
+
+#[cfg(unix)]
+#[test]
+fn test_mkdir_octal_mode() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path().join("private");
+
+    let mut cmd = Command::cargo_bin("mkdir").unwrap();
+    cmd.arg("-m").arg("700").arg(&dir);
+    cmd.assert().success();
+    assert_eq!(mode(&dir), 0o700);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_mkdir_symbolic_mode() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path().join("shared");
+
+    let mut cmd = Command::cargo_bin("mkdir").unwrap();
+    cmd.arg("--mode=u=rwx,g=rx,o=").arg(&dir);
+    cmd.assert().success();
+    assert_eq!(mode(&dir), 0o750);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_mkdir_rejects_invalid_mode() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path().join("never");
+
+    let mut cmd = Command::cargo_bin("mkdir").unwrap();
+    cmd.arg("-m").arg("u=q").arg(&dir);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("invalid mode 'u=q'"));
+    assert!(!dir.exists());
+}
+
+#[test]
+fn test_mkdir_pv_reports_each_new_parent() {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::create_dir(temp_dir.path().join("a")).unwrap();
+
+    let mut cmd = Command::cargo_bin("mkdir").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("-pv").arg("a/b/c");
+    cmd.assert().success().stdout(predicate::eq(
+        "created directory 'a/b'\ncreated directory 'a/b/c'\n",
+    ));
+    assert!(temp_dir.path().join("a/b/c").is_dir());
+}
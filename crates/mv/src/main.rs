@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
-use clap::Parser;
+use common::exit::UsageError;
+use clap::{Parser, ValueEnum};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
 #[derive(Parser, Debug)]
 #[command(name = "mv")]
@@ -20,50 +22,133 @@ struct Args {
     #[arg(short = 'v', long = "verbose")]
     verbose: bool,
 
-    /// Source file(s) or directory
-    #[arg(required = true)]
-    source: Vec<String>,
+    /// Move only when the source is newer than the destination, or the destination is missing
+    #[arg(short = 'u', long = "update")]
+    update: bool,
 
-    /// Destination file or directory
-    #[arg(required = true, last = true)]
-    destination: String,
+    /// Back up each destination before overwriting it (CONTROL: simple, numbered, existing)
+    #[arg(
+        short = 'b',
+        long = "backup",
+        value_name = "CONTROL",
+        value_enum,
+        num_args = 0..=1,
+        default_missing_value = "existing",
+        require_equals = true
+    )]
+    backup: Option<BackupControl>,
+
+    /// Suffix for simple backups (implies -b) [default: ~]
+    #[arg(short = 'S', long = "suffix", value_name = "SUFFIX")]
+    suffix: Option<String>,
+
+    /// Move every source into DIR
+    #[arg(
+        short = 't',
+        long = "target-directory",
+        value_name = "DIR",
+        allow_hyphen_values = true,
+        conflicts_with = "no_target_directory"
+    )]
+    target_directory: Option<String>,
+
+    /// Treat the destination as a plain name, never as a directory to move into
+    #[arg(short = 'T', long = "no-target-directory")]
+    no_target_directory: bool,
+
+    /// Sources, then the destination (unless -t gives it)
+    #[arg(required = true, value_name = "PATH")]
+    paths: Vec<String>,
+}
+
+/// How backups of overwritten destinations are named.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum BackupControl {
+    /// `dest~`, or `dest` plus the `-S` suffix
+    Simple,
+    /// `dest.~1~`, `dest.~2~`, ...
+    Numbered,
+    /// Numbered if numbered backups of `dest` already exist, else simple
+    Existing,
 }
 
-fn main() -> Result<()> {
-    let mut args = Args::parse();
+impl Args {
+    /// The backup naming to use, if backups are wanted at all. `-S` on
+    /// its own asks for them too, like GNU mv.
+    fn backup_control(&self) -> Option<BackupControl> {
+        self.backup
+            .or_else(|| self.suffix.as_ref().map(|_| BackupControl::Existing))
+    }
     
-    // Extract destination from source list
-    let destination = args.destination;
+    fn backup_suffix(&self) -> &str {
+        self.suffix.as_deref().unwrap_or("~")
+    }
+}
+
+fn main() -> ExitCode {
+    common::exit::report("mv", run())
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse();
     
-    // If only one source, simple move/rename
-    if args.source.len() == 1 {
-        move_file(&args.source[0], &destination, args.no_clobber, args.verbose)
-            .with_context(|| format!("Failed to move '{}' to '{}'", args.source[0], destination))?;
-    } else {
-        // Multiple sources - destination must be a directory
-        let dest_path = Path::new(&destination);
-        if !dest_path.exists() || !dest_path.is_dir() {
-            anyhow::bail!("target '{}' is not a directory", destination);
-        }
+    if let Some(directory) = &args.target_directory {
+        return move_into(&args.paths, directory, &args);
+    }
+    
+    let Some((destination, sources)) = args.paths.split_last() else {
+        anyhow::bail!(UsageError::new("missing file operand"));
+    };
+    if sources.is_empty() {
+        anyhow::bail!(UsageError::new(format!(
+            "missing destination file operand after '{}'",
+            destination
+        )));
+    }
+    
+    // With one source, an existing directory is moved into unless -T says
+    // the destination is just a name
+    if sources.len() == 1 && (args.no_target_directory || !Path::new(destination).is_dir()) {
+        return move_file(&sources[0], destination, &args)
+            .with_context(|| format!("Failed to move '{}' to '{}'", sources[0], destination));
+    }
+    
+    if args.no_target_directory {
+        anyhow::bail!(UsageError::new(format!("extra operand '{}'", args.paths[2])));
+    }
+    
+    move_into(sources, destination, &args)
+}
+
+/// Moves each of `sources` into `directory`, under its own name.
+fn move_into(sources: &[String], directory: &str, args: &Args) -> Result<()> {
+    let dest_path = Path::new(directory);
+    if !dest_path.exists() {
+        anyhow::bail!("target directory '{}' does not exist", directory);
+    }
+    if !dest_path.is_dir() {
+        anyhow::bail!("target '{}' is not a directory", directory);
+    }
+    
+    for source in sources {
+        let source_path = Path::new(source);
+        let file_name = source_path.file_name()
+            .ok_or_else(|| anyhow::anyhow!("Invalid source path: {}", source))?;
         
-        for source in &args.source {
-            let source_path = Path::new(source);
-            let file_name = source_path.file_name()
-                .ok_or_else(|| anyhow::anyhow!("Invalid source path: {}", source))?;
-            
-            let dest_file = dest_path.join(file_name);
-            let dest_str = dest_file.to_str()
-                .ok_or_else(|| anyhow::anyhow!("Invalid destination path"))?;
-            
-            move_file(source, dest_str, args.no_clobber, args.verbose)
-                .with_context(|| format!("Failed to move '{}' to '{}'", source, dest_str))?;
-        }
+        let dest_file = dest_path.join(file_name);
+        let dest_str = dest_file.to_str()
+            .ok_or_else(|| anyhow::anyhow!("Invalid destination path"))?;
+        
+        move_file(source, dest_str, args)
+            .with_context(|| format!("Failed to move '{}' to '{}'", source, dest_str))?;
     }
     
     Ok(())
 }
 
-fn move_file(source: &str, destination: &str, no_clobber: bool, verbose: bool) -> Result<()> {
+/// Moves `source` to exactly `destination`; the caller has already decided
+/// whether that means a path inside a directory.
+fn move_file(source: &str, destination: &str, args: &Args) -> Result<()> {
     let source_path = Path::new(source);
     let dest_path = Path::new(destination);
     
@@ -73,34 +158,125 @@ fn move_file(source: &str, destination: &str, no_clobber: bool, verbose: bool) -
     
     // Check if destination exists
     if dest_path.exists() {
-        if no_clobber {
-            return Ok(()); // Skip if no-clobber is set
+        if args.no_clobber {
+            return Ok(()); // Skip if no-clobber is set, before any backup
         }
         
-        // If destination is a directory and source is not, move into directory
-        if dest_path.is_dir() && !source_path.is_dir() {
-            let file_name = source_path.file_name()
-                .ok_or_else(|| anyhow::anyhow!("Invalid source path: {}", source))?;
-            let new_dest = dest_path.join(file_name);
-            return move_file(source, new_dest.to_str().unwrap(), no_clobber, verbose);
+        if args.update && !is_newer(source_path, dest_path)? {
+            return Ok(());
         }
     }
     
-    fs::rename(source_path, dest_path)?;
+    let backup = match args.backup_control() {
+        Some(control) if dest_path.exists() => {
+            let backup = backup_path(dest_path, control, args.backup_suffix())?;
+            fs::rename(dest_path, &backup)
+                .with_context(|| format!("cannot back up '{}'", destination))?;
+            Some(backup)
+        }
+        _ => None,
+    };
     
-    if verbose {
-        println!("'{}' -> '{}'", source, destination);
+    // Falls back to copying when the destination is on another file system
+    if let Err(e) = common::fs::move_path(source_path, dest_path) {
+        // Put the old destination back rather than leave only the backup
+        if let Some(backup) = &backup {
+            let _ = fs::rename(backup, dest_path);
+        }
+        return Err(e.into());
+    }
+    
+    if args.verbose {
+        match &backup {
+            Some(backup) => println!(
+                "'{}' -> '{}' (backup: '{}')",
+                source,
+                destination,
+                backup.display()
+            ),
+            None => println!("'{}' -> '{}'", source, destination),
+        }
     }
     
     Ok(())
 }
 
+/// Whether `source` was modified strictly later than `destination`.
+fn is_newer(source: &Path, destination: &Path) -> Result<bool> {
+    let source_time = fs::metadata(source)?.modified()?;
+    let destination_time = fs::metadata(destination)?.modified()?;
+    Ok(source_time > destination_time)
+}
+
+/// Chooses the name to back `destination` up to. Numbered backups count
+/// up from one past the highest `NAME.~N~` already beside it.
+fn backup_path(destination: &Path, control: BackupControl, suffix: &str) -> Result<PathBuf> {
+    let name = destination
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("cannot back up '{}'", destination.display()))?
+        .to_string_lossy()
+        .into_owned();
+    
+    let highest = highest_backup_number(destination, &name)?;
+    let numbered = match control {
+        BackupControl::Simple => false,
+        BackupControl::Numbered => true,
+        BackupControl::Existing => highest > 0,
+    };
+    
+    Ok(if numbered {
+        destination.with_file_name(format!("{}.~{}~", name, highest + 1))
+    } else {
+        destination.with_file_name(format!("{}{}", name, suffix))
+    })
+}
+
+/// The highest N among the `NAME.~N~` backups next to `destination`, or 0.
+fn highest_backup_number(destination: &Path, name: &str) -> Result<u64> {
+    let parent = match destination.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let prefix = format!("{}.~", name);
+    
+    let mut highest = 0;
+    for entry in fs::read_dir(parent)? {
+        let entry_name = entry?.file_name();
+        let number = entry_name
+            .to_str()
+            .and_then(|entry_name| entry_name.strip_prefix(&prefix))
+            .and_then(|rest| rest.strip_suffix('~'))
+            .and_then(|number| number.parse().ok());
+        if let Some(number) = number {
+            highest = highest.max(number);
+        }
+    }
+    
+    Ok(highest)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::env;
+    use std::fs;
     use std::fs::File;
     use std::io::Write;
+    use tempfile::TempDir;
+
+    fn args() -> Args {
+        Args {
+            force: false,
+            no_clobber: false,
+            verbose: false,
+            update: false,
+            backup: None,
+            suffix: None,
+            target_directory: None,
+            no_target_directory: false,
+            paths: vec![],
+        }
+    }
 
     #[test]
     fn test_move_file() {
@@ -117,8 +293,7 @@ mod tests {
         let result = move_file(
             source.to_str().unwrap(),
             dest.to_str().unwrap(),
-            false,
-            false
+            &args()
         );
         
         assert!(result.is_ok());
@@ -145,8 +320,7 @@ mod tests {
         let result = move_file(
             source.to_str().unwrap(),
             dest.to_str().unwrap(),
-            false,
-            false
+            &args()
         );
         
         assert!(result.is_ok());
@@ -163,8 +337,150 @@ mod tests {
 
     #[test]
     fn test_move_nonexistent_file() {
-        let result = move_file("/nonexistent_12345.txt", "/dest.txt", false, false);
+        let result = move_file("/nonexistent_12345.txt", "/dest.txt", &args());
         assert!(result.is_err());
     }
-}
 
+    #[test]
+    fn test_simple_backup() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("new.txt");
+        let dest = dir.path().join("old.txt");
+        fs::write(&source, "new").unwrap();
+        fs::write(&dest, "old").unwrap();
+        
+        let args = Args {
+            backup: Some(BackupControl::Simple),
+            ..args()
+        };
+        move_file(source.to_str().unwrap(), dest.to_str().unwrap(), &args).unwrap();
+        
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "new");
+        assert_eq!(fs::read_to_string(dir.path().join("old.txt~")).unwrap(), "old");
+    }
+
+    #[test]
+    fn test_suffix_implies_backup() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("new.txt");
+        let dest = dir.path().join("old.txt");
+        fs::write(&source, "new").unwrap();
+        fs::write(&dest, "old").unwrap();
+        
+        let args = Args {
+            suffix: Some(".bak".to_string()),
+            ..args()
+        };
+        move_file(source.to_str().unwrap(), dest.to_str().unwrap(), &args).unwrap();
+        
+        assert_eq!(fs::read_to_string(dir.path().join("old.txt.bak")).unwrap(), "old");
+    }
+
+    #[test]
+    fn test_numbered_backups_count_up() {
+        let dir = TempDir::new().unwrap();
+        let dest = dir.path().join("file");
+        fs::write(&dest, "0").unwrap();
+        
+        let args = Args {
+            backup: Some(BackupControl::Numbered),
+            ..args()
+        };
+        for content in ["1", "2"] {
+            let source = dir.path().join("source");
+            fs::write(&source, content).unwrap();
+            move_file(source.to_str().unwrap(), dest.to_str().unwrap(), &args).unwrap();
+        }
+        
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "2");
+        assert_eq!(fs::read_to_string(dir.path().join("file.~1~")).unwrap(), "0");
+        assert_eq!(fs::read_to_string(dir.path().join("file.~2~")).unwrap(), "1");
+    }
+
+    #[test]
+    fn test_existing_backup_control() {
+        let dir = TempDir::new().unwrap();
+        let dest = dir.path().join("file");
+        fs::write(&dest, "").unwrap();
+        
+        // Simple until a numbered backup exists, then numbered
+        assert_eq!(
+            backup_path(&dest, BackupControl::Existing, "~").unwrap(),
+            dir.path().join("file~")
+        );
+        fs::write(dir.path().join("file.~7~"), "").unwrap();
+        assert_eq!(
+            backup_path(&dest, BackupControl::Existing, "~").unwrap(),
+            dir.path().join("file.~8~")
+        );
+    }
+
+    #[test]
+    fn test_no_clobber_skips_before_backup() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("new.txt");
+        let dest = dir.path().join("old.txt");
+        fs::write(&source, "new").unwrap();
+        fs::write(&dest, "old").unwrap();
+        
+        let args = Args {
+            no_clobber: true,
+            backup: Some(BackupControl::Simple),
+            ..args()
+        };
+        move_file(source.to_str().unwrap(), dest.to_str().unwrap(), &args).unwrap();
+        
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "old");
+        assert!(source.exists());
+        assert!(!dir.path().join("old.txt~").exists());
+    }
+
+    #[test]
+    fn test_update_moves_only_newer_sources() {
+        let dir = TempDir::new().unwrap();
+        let older = dir.path().join("older.txt");
+        let newer = dir.path().join("newer.txt");
+        fs::write(&older, "older").unwrap();
+        fs::write(&newer, "newer").unwrap();
+        filetime::set_file_mtime(&older, filetime::FileTime::from_unix_time(1_000, 0)).unwrap();
+        filetime::set_file_mtime(&newer, filetime::FileTime::from_unix_time(2_000, 0)).unwrap();
+        
+        let args = Args {
+            update: true,
+            ..args()
+        };
+        
+        // The older file doesn't replace the newer one
+        move_file(older.to_str().unwrap(), newer.to_str().unwrap(), &args).unwrap();
+        assert!(older.exists());
+        assert_eq!(fs::read_to_string(&newer).unwrap(), "newer");
+        
+        // But the newer one replaces the older one
+        move_file(newer.to_str().unwrap(), older.to_str().unwrap(), &args).unwrap();
+        assert!(!newer.exists());
+        assert_eq!(fs::read_to_string(&older).unwrap(), "newer");
+    }
+
+    #[test]
+    fn test_update_skips_equal_times_and_moves_to_missing_destinations() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("source.txt");
+        let dest = dir.path().join("dest.txt");
+        fs::write(&source, "source").unwrap();
+        fs::write(&dest, "dest").unwrap();
+        let time = filetime::FileTime::from_unix_time(1_000, 0);
+        filetime::set_file_mtime(&source, time).unwrap();
+        filetime::set_file_mtime(&dest, time).unwrap();
+        
+        let args = Args {
+            update: true,
+            ..args()
+        };
+        move_file(source.to_str().unwrap(), dest.to_str().unwrap(), &args).unwrap();
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "dest");
+        
+        let missing = dir.path().join("missing.txt");
+        move_file(source.to_str().unwrap(), missing.to_str().unwrap(), &args).unwrap();
+        assert_eq!(fs::read_to_string(&missing).unwrap(), "source");
+    }
+}
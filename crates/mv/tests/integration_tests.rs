@@ -0,0 +1,132 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_mv_renames_a_file() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("old.txt"), "data").unwrap();
+    
+    let mut cmd = Command::cargo_bin("mv").unwrap();
+    cmd.current_dir(dir.path()).arg("old.txt").arg("new.txt");
+    cmd.assert().success();
+    
+    assert!(!dir.path().join("old.txt").exists());
+    assert_eq!(fs::read_to_string(dir.path().join("new.txt")).unwrap(), "data");
+}
+
+#[test]
+fn test_mv_moves_into_an_existing_directory() {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("target")).unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    fs::write(dir.path().join("file.txt"), "data").unwrap();
+    
+    let mut cmd = Command::cargo_bin("mv").unwrap();
+    cmd.current_dir(dir.path()).arg("file.txt").arg("target");
+    cmd.assert().success();
+    assert!(dir.path().join("target/file.txt").exists());
+    
+    // Directories are moved into it too, not renamed over it
+    let mut cmd = Command::cargo_bin("mv").unwrap();
+    cmd.current_dir(dir.path()).arg("sub").arg("target");
+    cmd.assert().success();
+    assert!(dir.path().join("target/sub").is_dir());
+}
+
+#[test]
+fn test_mv_target_directory() {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("-dir")).unwrap();
+    fs::write(dir.path().join("a.txt"), "a").unwrap();
+    fs::write(dir.path().join("b.txt"), "b").unwrap();
+    
+    // A directory name that looks like an option is fine after -t
+    let mut cmd = Command::cargo_bin("mv").unwrap();
+    cmd.current_dir(dir.path())
+        .arg("-t")
+        .arg("-dir")
+        .arg("a.txt")
+        .arg("b.txt");
+    cmd.assert().success();
+    
+    assert!(dir.path().join("-dir/a.txt").exists());
+    assert!(dir.path().join("-dir/b.txt").exists());
+}
+
+#[test]
+fn test_mv_target_directory_must_exist() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("a.txt"), "a").unwrap();
+    
+    let mut cmd = Command::cargo_bin("mv").unwrap();
+    cmd.current_dir(dir.path()).arg("-t").arg("missing").arg("a.txt");
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("target directory 'missing' does not exist"));
+    
+    let mut cmd = Command::cargo_bin("mv").unwrap();
+    cmd.current_dir(dir.path()).arg("--target-directory=a.txt").arg("a.txt");
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("target 'a.txt' is not a directory"));
+    assert!(dir.path().join("a.txt").exists());
+}
+
+#[test]
+fn test_mv_no_target_directory_renames_over_an_empty_directory() {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("src/file.txt"), "data").unwrap();
+    fs::create_dir(dir.path().join("dest")).unwrap();
+    
+    let mut cmd = Command::cargo_bin("mv").unwrap();
+    cmd.current_dir(dir.path()).arg("-T").arg("src").arg("dest");
+    cmd.assert().success();
+    
+    assert!(dir.path().join("dest/file.txt").exists());
+    assert!(!dir.path().join("dest/src").exists());
+}
+
+#[test]
+fn test_mv_no_target_directory_takes_two_operands() {
+    let mut cmd = Command::cargo_bin("mv").unwrap();
+    cmd.arg("-T").arg("a").arg("b").arg("c");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("extra operand 'c'"));
+}
+
+#[test]
+fn test_mv_target_directory_flags_conflict() {
+    let mut cmd = Command::cargo_bin("mv").unwrap();
+    cmd.arg("-T").arg("-t").arg("dir").arg("a");
+    cmd.assert().code(2);
+}
+
+#[test]
+fn test_mv_missing_destination() {
+    let mut cmd = Command::cargo_bin("mv").unwrap();
+    cmd.arg("only.txt");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("missing destination file operand after 'only.txt'"));
+}
+
+#[test]
+fn test_mv_verbose_is_silent_when_update_skips() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("old.txt"), "old").unwrap();
+    fs::write(dir.path().join("new.txt"), "new").unwrap();
+    let old = filetime::FileTime::from_unix_time(1_000, 0);
+    filetime::set_file_mtime(dir.path().join("old.txt"), old).unwrap();
+    
+    let mut cmd = Command::cargo_bin("mv").unwrap();
+    cmd.current_dir(dir.path()).arg("-uv").arg("old.txt").arg("new.txt");
+    cmd.assert().success().stdout("");
+    
+    let mut cmd = Command::cargo_bin("mv").unwrap();
+    cmd.current_dir(dir.path()).arg("-uv").arg("new.txt").arg("old.txt");
+    cmd.assert().success().stdout("'new.txt' -> 'old.txt'\n");
+}
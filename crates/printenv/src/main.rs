@@ -0,0 +1,75 @@
+use anyhow::Result;
+use clap::Parser;
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+#[derive(Parser, Debug)]
+#[command(name = "printenv")]
+#[command(about = "Print all or part of the environment", long_about = None)]
+#[command(version)]
+struct Args {
+    /// End each output line with NUL, not newline
+    #[arg(short = '0', long = "null")]
+    null: bool,
+
+    /// Variables to print (all variables if none are given)
+    names: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    common::exit::report("printenv", run())
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse();
+    let terminator = common::io::record_separator(args.null);
+
+    let mut stdout = common::io::stdout_writer();
+    let all_found = if args.names.is_empty() {
+        common::io::write_environment(&mut stdout, std::env::vars_os(), terminator)?;
+        true
+    } else {
+        print_named(&mut stdout, &args.names, terminator)?
+    };
+    stdout.flush()?;
+
+    // Like GNU printenv, a missing variable is reported only by exit status
+    if !all_found {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Prints the value of each variable in `names`, returning whether every
+/// one of them was set.
+fn print_named<W: Write>(writer: &mut W, names: &[String], terminator: u8) -> io::Result<bool> {
+    let mut all_found = true;
+
+    for name in names {
+        match std::env::var_os(name) {
+            Some(value) if !name.contains('=') => {
+                writer.write_all(value.to_string_lossy().as_bytes())?;
+                writer.write_all(&[terminator])?;
+            }
+            _ => all_found = false,
+        }
+    }
+
+    Ok(all_found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_print_named_reports_missing() {
+        let mut output = Vec::new();
+        let names = vec!["PATH".to_string(), "PRINTENV_SURELY_UNSET".to_string()];
+        let found = print_named(&mut output, &names, b'\0').unwrap();
+        assert!(!found);
+        let path = std::env::var("PATH").unwrap();
+        assert_eq!(output, format!("{}\0", path).as_bytes());
+    }
+}
@@ -0,0 +1,27 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn test_printenv_named_variable() {
+    let mut cmd = Command::cargo_bin("printenv").unwrap();
+    cmd.env("PRINTENV_TEST", "hello world").arg("PRINTENV_TEST");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("hello world\n"));
+}
+
+#[test]
+fn test_printenv_lists_all() {
+    let mut cmd = Command::cargo_bin("printenv").unwrap();
+    cmd.env("PRINTENV_TEST", "1");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("PRINTENV_TEST=1\n"));
+}
+
+#[test]
+fn test_printenv_missing_variable_fails() {
+    let mut cmd = Command::cargo_bin("printenv").unwrap();
+    cmd.env_remove("PRINTENV_TEST").arg("PRINTENV_TEST");
+    cmd.assert().code(1).stdout(predicate::str::is_empty());
+}
@@ -0,0 +1,360 @@
+use anyhow::Result;
+use clap::Parser;
+use common::escape::interpret_escapes_bytes;
+use std::io::Write;
+use std::iter::Peekable;
+use std::process::ExitCode;
+use std::str::Chars;
+
+#[derive(Parser, Debug)]
+#[command(name = "printf")]
+#[command(about = "Format and print data", long_about = None)]
+#[command(version)]
+struct Args {
+    /// Format string with %s, %d, %x, %o, %c and %% conversions
+    #[arg(allow_hyphen_values = true)]
+    format: String,
+
+    /// Arguments consumed by the conversions in FORMAT
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    arguments: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    common::exit::report("printf", run())
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse();
+
+    let output = printf(&args.format, &args.arguments)?;
+
+    let mut stdout = common::io::stdout_writer();
+    stdout.write_all(&output)?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
+/// Formats `arguments` according to `format`, reusing the format for as
+/// long as arguments remain. The output is raw bytes, since escapes such
+/// as `\xff` needn't make valid UTF-8.
+fn printf(format: &str, arguments: &[String]) -> Result<Vec<u8>> {
+    let mut output = Vec::new();
+    let mut remaining = arguments;
+
+    loop {
+        let pass = format_once(format, remaining, &mut output)?;
+        remaining = &remaining[pass.used..];
+
+        if pass.stopped || pass.used == 0 || remaining.is_empty() {
+            break;
+        }
+    }
+
+    Ok(output)
+}
+
+/// What one run through the format did.
+struct Pass {
+    /// How many arguments the conversions took
+    used: usize,
+    /// A `\c` ended all output
+    stopped: bool,
+}
+
+/// Runs through `format` once, appending to `output`.
+///
+/// Escapes are interpreted in the text between conversions, so one that
+/// stands for `%`, like `\045`, prints a `%` rather than starting a
+/// conversion.
+fn format_once(format: &str, arguments: &[String], output: &mut Vec<u8>) -> Result<Pass> {
+    let mut chars = format.chars().peekable();
+    let mut literal = String::new();
+    let mut used = 0;
+
+    while let Some(ch) = chars.next() {
+        if ch != '%' {
+            literal.push(ch);
+            continue;
+        }
+
+        if write_literal(&mut literal, output) {
+            return Ok(Pass { used, stopped: true });
+        }
+
+        let spec = Spec::parse(&mut chars)?;
+        if spec.conversion == '%' {
+            output.push(b'%');
+            continue;
+        }
+
+        let argument = arguments.get(used).map(String::as_str);
+        if argument.is_some() {
+            used += 1;
+        }
+        output.extend_from_slice(spec.format(argument.unwrap_or(""))?.as_bytes());
+    }
+
+    let stopped = write_literal(&mut literal, output);
+    Ok(Pass { used, stopped })
+}
+
+/// Appends `literal` to `output` with its escapes interpreted, and empties
+/// it. Returns whether a `\c` stopped the output.
+fn write_literal(literal: &mut String, output: &mut Vec<u8>) -> bool {
+    let escaped = interpret_escapes_bytes(literal);
+    output.extend_from_slice(&escaped.bytes);
+    literal.clear();
+    escaped.stopped
+}
+
+/// A single `%` conversion: flags, width, precision and conversion letter.
+#[derive(Debug, Default, PartialEq)]
+struct Spec {
+    left_align: bool,
+    zero_pad: bool,
+    plus_sign: bool,
+    space_sign: bool,
+    alternate: bool,
+    width: Option<usize>,
+    precision: Option<usize>,
+    conversion: char,
+}
+
+impl Spec {
+    /// Parses the part of a conversion after its `%`.
+    fn parse(chars: &mut Peekable<Chars>) -> Result<Self> {
+        let mut spec = Spec::default();
+
+        while let Some(&flag) = chars.peek() {
+            match flag {
+                '-' => spec.left_align = true,
+                '0' => spec.zero_pad = true,
+                '+' => spec.plus_sign = true,
+                ' ' => spec.space_sign = true,
+                '#' => spec.alternate = true,
+                _ => break,
+            }
+            chars.next();
+        }
+
+        spec.width = parse_digits(chars);
+        if chars.peek() == Some(&'.') {
+            chars.next();
+            spec.precision = Some(parse_digits(chars).unwrap_or(0));
+        }
+
+        spec.conversion = match chars.next() {
+            Some(c @ ('s' | 'c' | 'd' | 'i' | 'u' | 'x' | 'X' | 'o' | '%')) => c,
+            Some(c) => anyhow::bail!("%{}: invalid conversion specification", c),
+            None => anyhow::bail!("missing format character"),
+        };
+
+        Ok(spec)
+    }
+
+    fn format(&self, argument: &str) -> Result<String> {
+        let (sign, body) = match self.conversion {
+            's' => {
+                let text = match self.precision {
+                    Some(precision) => argument.chars().take(precision).collect(),
+                    None => argument.to_string(),
+                };
+                return Ok(self.pad("", &text, false));
+            }
+            'c' => {
+                let text = argument
+                    .chars()
+                    .next()
+                    .map(String::from)
+                    .unwrap_or_default();
+                return Ok(self.pad("", &text, false));
+            }
+            'd' | 'i' => {
+                let value = parse_integer(argument)?;
+                let sign = if value < 0 {
+                    "-"
+                } else if self.plus_sign {
+                    "+"
+                } else if self.space_sign {
+                    " "
+                } else {
+                    ""
+                };
+                (sign, self.with_precision(value.unsigned_abs().to_string()))
+            }
+            _ => {
+                // Negative values wrap like C's unsigned conversions
+                let value = parse_integer(argument)? as u64;
+                let digits = match self.conversion {
+                    'x' => format!("{:x}", value),
+                    'X' => format!("{:X}", value),
+                    'o' => format!("{:o}", value),
+                    _ => value.to_string(),
+                };
+                let mut digits = self.with_precision(digits);
+
+                let prefix = match self.conversion {
+                    'x' if self.alternate && value != 0 => "0x",
+                    'X' if self.alternate && value != 0 => "0X",
+                    'o' if self.alternate && !digits.starts_with('0') => {
+                        digits.insert(0, '0');
+                        ""
+                    }
+                    _ => "",
+                };
+                (prefix, digits)
+            }
+        };
+
+        Ok(self.pad(sign, &body, true))
+    }
+
+    /// Left-pads `digits` with zeros up to the precision, if one was given.
+    fn with_precision(&self, digits: String) -> String {
+        match self.precision {
+            Some(precision) if digits.len() < precision => {
+                format!("{}{}", "0".repeat(precision - digits.len()), digits)
+            }
+            _ => digits,
+        }
+    }
+
+    /// Pads `prefix` + `body` to the field width.
+    fn pad(&self, prefix: &str, body: &str, numeric: bool) -> String {
+        let len = prefix.chars().count() + body.chars().count();
+        let fill = self.width.unwrap_or(0).saturating_sub(len);
+
+        if self.left_align {
+            format!("{}{}{}", prefix, body, " ".repeat(fill))
+        } else if self.zero_pad && numeric && self.precision.is_none() {
+            format!("{}{}{}", prefix, "0".repeat(fill), body)
+        } else {
+            format!("{}{}{}", " ".repeat(fill), prefix, body)
+        }
+    }
+}
+
+fn parse_digits(chars: &mut Peekable<Chars>) -> Option<usize> {
+    let mut value = None;
+
+    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+        value = Some(value.unwrap_or(0) * 10 + digit as usize);
+        chars.next();
+    }
+
+    value
+}
+
+/// Parses a numeric argument: decimal, `0x` hex, leading-zero octal, or
+/// `'c` for the code point of `c`.
+fn parse_integer(argument: &str) -> Result<i64> {
+    let invalid = || anyhow::anyhow!("invalid number: '{}'", argument);
+    let trimmed = argument.trim();
+
+    if let Some(quoted) = trimmed.strip_prefix(['\'', '"']) {
+        return Ok(quoted.chars().next().map_or(0, |c| c as i64));
+    }
+    if trimmed.is_empty() {
+        return Ok(0);
+    }
+
+    let (negative, digits) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+
+    let magnitude = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        i64::from_str_radix(hex, 16)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i64::from_str_radix(&digits[1..], 8)
+    } else {
+        digits.parse()
+    }
+    .map_err(|_| invalid())?;
+
+    Ok(if negative { -magnitude } else { magnitude })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(format: &str, arguments: &[&str]) -> String {
+        String::from_utf8(run_bytes(format, arguments)).unwrap()
+    }
+
+    fn run_bytes(format: &str, arguments: &[&str]) -> Vec<u8> {
+        let arguments: Vec<String> = arguments.iter().map(|s| s.to_string()).collect();
+        printf(format, &arguments).unwrap()
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(run("%d\\n", &["42"]), "42\n");
+        assert_eq!(run("%+d %d", &["7", "-7"]), "+7 -7");
+        assert_eq!(run("%05d|%-5d|%.3d", &["-42", "3", "9"]), "-0042|3    |009");
+    }
+
+    #[test]
+    fn test_string_padding() {
+        assert_eq!(run("[%5s]", &["ab"]), "[   ab]");
+        assert_eq!(run("[%-5s]", &["ab"]), "[ab   ]");
+        assert_eq!(run("[%.2s]", &["abcdef"]), "[ab]");
+    }
+
+    #[test]
+    fn test_format_recycled_for_extra_arguments() {
+        assert_eq!(run("%s=%d\\n", &["a", "1", "b", "2"]), "a=1\nb=2\n");
+        assert_eq!(run("<%s>", &["x", "y", "z"]), "<x><y><z>");
+    }
+
+    #[test]
+    fn test_missing_arguments_are_empty_or_zero() {
+        assert_eq!(run("%s=%d;", &["a", "1", "b"]), "a=1;b=0;");
+        assert_eq!(run("plain\\n", &["ignored"]), "plain\n");
+    }
+
+    #[test]
+    fn test_hex_octal_char_and_percent() {
+        assert_eq!(run("%x %X %#x", &["255", "255", "255"]), "ff FF 0xff");
+        assert_eq!(run("%o %#o", &["8", "8"]), "10 010");
+        assert_eq!(run("%c%c", &["hello", "!"]), "h!");
+        assert_eq!(run("100%%", &[]), "100%");
+    }
+
+    #[test]
+    fn test_escaped_percent_is_not_a_conversion() {
+        assert_eq!(run("\\045d\\n", &["5"]), "%d\n");
+        assert_eq!(run("\\x25s %s", &["a"]), "%s a");
+    }
+
+    #[test]
+    fn test_escapes_write_raw_bytes() {
+        assert_eq!(run_bytes("\\xff", &[]), [0xFF]);
+        assert_eq!(run_bytes("%s\\377\\n", &["a"]), [b'a', 0xFF, b'\n']);
+    }
+
+    #[test]
+    fn test_c_stops_all_output() {
+        assert_eq!(run("%s\\c.", &["a", "b"]), "a");
+    }
+
+    #[test]
+    fn test_numeric_argument_forms() {
+        assert_eq!(parse_integer("0x1f").unwrap(), 31);
+        assert_eq!(parse_integer("017").unwrap(), 15);
+        assert_eq!(parse_integer("'A").unwrap(), 65);
+        assert!(parse_integer("12abc").is_err());
+    }
+
+    #[test]
+    fn test_invalid_conversion() {
+        assert!(printf("%q", &[]).is_err());
+        assert!(printf("trailing %", &[]).is_err());
+    }
+}
@@ -0,0 +1,20 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn test_printf_recycles_format() {
+    let mut cmd = Command::cargo_bin("printf").unwrap();
+    cmd.arg("%s is %d\\n").arg("a").arg("1").arg("b").arg("-2");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("a is 1\nb is -2\n"));
+}
+
+#[test]
+fn test_printf_invalid_number() {
+    let mut cmd = Command::cargo_bin("printf").unwrap();
+    cmd.arg("%d").arg("abc");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid number"));
+}
@@ -1,7 +1,10 @@
 use anyhow::{Context, Result};
 use clap::Parser;
 use std::env;
+use std::fs;
 use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::ExitCode;
 
 #[derive(Parser, Debug)]
 #[command(name = "pwd")]
@@ -17,7 +20,11 @@ struct Args {
     physical: bool,
 }
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
+    common::exit::report("pwd", run())
+}
+
+fn run() -> Result<()> {
     let args = Args::parse();
     
     let current_dir = get_current_directory(&args)?;
@@ -29,14 +36,16 @@ fn main() -> Result<()> {
 fn get_current_directory(args: &Args) -> Result<String> {
     let path = if args.physical {
         // Get the canonicalized (physical) path
-        env::current_dir()
-            .context("Failed to get current directory")?
+        working_directory()?
             .canonicalize()
             .context("Failed to canonicalize path")?
     } else {
-        // Get the logical path (default)
-        env::current_dir()
-            .context("Failed to get current directory")?
+        // Get the logical path (default), which is $PWD when it can be
+        // trusted, as it keeps the symlinks `cd` went through
+        match logical_pwd() {
+            Some(pwd) => pwd,
+            None => working_directory()?,
+        }
     };
     
     path.to_str()
@@ -44,6 +53,43 @@ fn get_current_directory(args: &Args) -> Result<String> {
         .map(|s| s.to_string())
 }
 
+/// The current directory as the OS reports it, which fails if the
+/// directory has been removed.
+fn working_directory() -> Result<PathBuf> {
+    env::current_dir().map_err(|_| {
+        anyhow::anyhow!("error retrieving current directory: getcwd: cannot access parent directories")
+    })
+}
+
+/// `$PWD`, if it is an absolute path without `.` or `..` components that
+/// names the current directory.
+fn logical_pwd() -> Option<PathBuf> {
+    let pwd = PathBuf::from(env::var_os("PWD")?);
+    let clean = pwd.is_absolute()
+        && pwd
+            .components()
+            .all(|component| !matches!(component, Component::CurDir | Component::ParentDir));
+    
+    (clean && same_directory(&pwd, Path::new("."))).then_some(pwd)
+}
+
+#[cfg(unix)]
+fn same_directory(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_directory(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
 fn print_directory(dir: &str) -> Result<()> {
     let stdout = io::stdout();
     let mut handle = stdout.lock();
@@ -72,6 +118,13 @@ mod tests {
         assert!(!dir.is_empty());
     }
 
+    #[test]
+    fn test_same_directory() {
+        let current = env::current_dir().unwrap();
+        assert!(same_directory(&current, Path::new(".")));
+        assert!(!same_directory(&current, Path::new("/nonexistent_dir_12345")));
+    }
+
     #[test]
     fn test_print_directory() {
         let result = print_directory("/test/path");
@@ -33,3 +33,65 @@ fn test_pwd_physical_flag() {
     cmd.assert().success();
 }
 
+
+#[cfg(unix)]
+#[test]
+fn test_pwd_logical_keeps_symlinks_in_pwd() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let real = temp_dir.path().join("real");
+    let link = temp_dir.path().join("link");
+    std::fs::create_dir(&real).unwrap();
+    std::os::unix::fs::symlink(&real, &link).unwrap();
+
+    let mut cmd = Command::cargo_bin("pwd").unwrap();
+    cmd.current_dir(&link).env("PWD", &link).arg("-L");
+    cmd.assert()
+        .success()
+        .stdout(format!("{}\n", link.display()));
+
+    let mut cmd = Command::cargo_bin("pwd").unwrap();
+    cmd.current_dir(&link).env("PWD", &link).arg("-P");
+    cmd.assert()
+        .success()
+        .stdout(format!("{}\n", real.canonicalize().unwrap().display()));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pwd_logical_ignores_a_stale_pwd() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let here = temp_dir.path().join("here");
+    let elsewhere = temp_dir.path().join("elsewhere");
+    std::fs::create_dir(&here).unwrap();
+    std::fs::create_dir(&elsewhere).unwrap();
+
+    for pwd in [elsewhere, here.join("../here")] {
+        let mut cmd = Command::cargo_bin("pwd").unwrap();
+        cmd.current_dir(&here).env("PWD", &pwd).arg("-L");
+        cmd.assert()
+            .success()
+            .stdout(format!("{}\n", here.canonicalize().unwrap().display()));
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pwd_in_removed_directory() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let gone = temp_dir.path().join("gone");
+
+    // Only a shell can leave pwd started in a directory that no longer exists
+    for flag in ["-L", "-P"] {
+        std::fs::create_dir(&gone).unwrap();
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(r#"cd "$1" && rmdir "$1" && exec "$0" "$2""#)
+            .arg(assert_cmd::cargo::cargo_bin("pwd"))
+            .arg(&gone)
+            .arg(flag)
+            .env_remove("PWD");
+        cmd.assert().code(1).stdout("").stderr(
+            "pwd: error retrieving current directory: getcwd: cannot access parent directories\n",
+        );
+    }
+}
@@ -1,9 +1,17 @@
 use anyhow::{Context, Result};
 use clap::Parser;
+use std::env;
+use std::ffi::{OsStr, OsString};
 use std::fs;
-use std::path::Path;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant, SystemTime};
 
-#[derive(Parser, Debug)]
+/// How often `--progress` redraws its count.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Parser, Debug, Clone)]
 #[command(name = "rm")]
 #[command(about = "Remove files or directories", long_about = None)]
 #[command(version)]
@@ -13,9 +21,17 @@ struct Args {
     recursive: bool,
 
     /// Force removal without prompting
-    #[arg(short = 'f', long = "force")]
+    #[arg(short = 'f', long = "force", overrides_with_all = ["interactive", "interactive_once"])]
     force: bool,
 
+    /// Prompt before every removal
+    #[arg(short = 'i', overrides_with_all = ["force", "interactive_once"])]
+    interactive: bool,
+
+    /// Prompt once before removing more than three files or removing recursively
+    #[arg(short = 'I', overrides_with_all = ["force", "interactive"])]
+    interactive_once: bool,
+
     /// Verbose mode
     #[arg(short = 'v', long = "verbose")]
     verbose: bool,
@@ -24,54 +40,224 @@ struct Args {
     #[arg(short = 'd', long = "dir")]
     dir: bool,
 
+    /// Show a running count of removed entries on stderr (terminals only)
+    #[arg(long = "progress")]
+    progress: bool,
+
+    /// Refuse to remove '/' recursively (the default)
+    #[arg(long = "preserve-root", overrides_with = "no_preserve_root")]
+    preserve_root: bool,
+
+    /// Let -r remove '/' as well
+    #[arg(long = "no-preserve-root", overrides_with = "preserve_root")]
+    no_preserve_root: bool,
+
+    /// When removing recursively, skip directories on other file systems
+    #[arg(long = "one-file-system")]
+    one_file_system: bool,
+
+    /// Move files to the trash instead of deleting them
+    #[arg(long = "trash")]
+    trash: bool,
+
+    /// Expand `*`, `?`, and `[...]` in operands that don't name an existing
+    /// file, for when no shell has done so
+    #[arg(long = "glob")]
+    glob: bool,
+
     /// Files or directories to remove
     #[arg(required = true)]
     files: Vec<String>,
 }
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
+    // A path that couldn't be removed has already been reported, so all
+    // that's left is the exit status
+    match run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(common::exit::FAILURE),
+        Err(error) => common::exit::report("rm", Err::<(), _>(error)),
+    }
+}
+
+/// Running count of removed entries, redrawn in place on stderr for
+/// `--progress`.
+struct Progress {
+    removed: u64,
+    display: bool,
+    last_draw: Instant,
+}
+
+impl Progress {
+    fn new(enabled: bool) -> Self {
+        Progress {
+            removed: 0,
+            display: enabled && io::stderr().is_terminal(),
+            last_draw: Instant::now(),
+        }
+    }
+    
+    fn record(&mut self) {
+        self.removed += 1;
+        if self.display && self.last_draw.elapsed() >= PROGRESS_INTERVAL {
+            self.draw();
+        }
+    }
+    
+    fn draw(&mut self) {
+        eprint!("\rremoved {} entries", self.removed);
+        self.last_draw = Instant::now();
+    }
+    
+    fn finish(&mut self) {
+        if self.display {
+            self.draw();
+            eprintln!();
+        }
+    }
+}
+
+fn run() -> Result<bool> {
     let args = Args::parse();
+    let mut progress = Progress::new(args.progress);
+    
+    let result = remove_all(&args, &mut progress);
+    progress.finish();
+    result
+}
+
+/// Removes every operand, reporting each one that fails on stderr and
+/// carrying on with the rest. Returns whether they all went through.
+fn remove_all(args: &Args, progress: &mut Progress) -> Result<bool> {
+    if args.one_file_system && cfg!(not(unix)) {
+        eprintln!("rm: warning: --one-file-system is not supported on this platform");
+    }
+    
+    let trash = if args.trash {
+        let trash = trash_directory()?;
+        trash.create()?;
+        Some(trash)
+    } else {
+        None
+    };
     
+    let mut all_removed = true;
+    let mut paths = Vec::new();
     for file in &args.files {
-        match remove_path(file, &args) {
-            Ok(_) => {}
+        match operand_paths(file, args) {
+            Ok(matches) => paths.extend(matches),
             Err(e) => {
-                if !args.force {
-                    return Err(e).with_context(|| format!("Failed to remove '{}'", file));
-                }
-                // With -f, silently ignore errors
+                common::exit::report("rm", Err::<(), _>(e));
+                all_removed = false;
+            }
+        }
+    }
+    
+    if args.interactive_once && (args.recursive || paths.len() > 3) {
+        let count = paths.len();
+        let question = format!(
+            "remove {} argument{}{}?",
+            count,
+            if count == 1 { "" } else { "s" },
+            if args.recursive { " recursively" } else { "" }
+        );
+        if !confirm(&question)? {
+            return Ok(all_removed);
+        }
+    }
+    
+    for file in &paths {
+        // -f already passes over missing files; anything else is an error
+        // worth hearing about
+        let result = remove_operand(file, args, trash.as_ref(), progress)
+            .with_context(|| format!("Failed to remove '{}'", file));
+        match result {
+            Ok(true) => {}
+            // Whatever failed inside a directory has been reported already
+            Ok(false) => all_removed = false,
+            Err(e) => {
+                common::exit::report("rm", Err::<(), _>(e));
+                all_removed = false;
             }
         }
     }
     
-    Ok(())
+    Ok(all_removed)
+}
+
+/// The paths `file` stands for: just itself, unless `--glob` is on and
+/// it is a pattern rather than an existing path, in which case every path
+/// it matches.
+fn operand_paths(file: &str, args: &Args) -> Result<Vec<String>> {
+    if !args.glob || !common::glob::is_pattern(file) || fs::symlink_metadata(file).is_ok() {
+        return Ok(vec![file.to_string()]);
+    }
+    
+    let matches = common::glob::expand(file);
+    if matches.is_empty() && !args.force {
+        anyhow::bail!("cannot remove '{}': No such file or directory", file);
+    }
+    Ok(matches)
+}
+
+/// Removes or trashes one operand. Returns whether everything under it
+/// went too; entries inside a directory that couldn't be removed are
+/// reported as they fail.
+fn remove_operand(
+    file: &str,
+    args: &Args,
+    trash: Option<&Trash>,
+    progress: &mut Progress,
+) -> Result<bool> {
+    if args.recursive && !args.no_preserve_root && is_root(Path::new(file)) {
+        anyhow::bail!(
+            "it is dangerous to operate recursively on '{}' (use --no-preserve-root to override)",
+            file
+        );
+    }
+    
+    match trash {
+        Some(trash) => move_to_trash(file, args, trash, progress),
+        None => remove_path(file, args, progress),
+    }
 }
 
-fn remove_path(path: &str, args: &Args) -> Result<()> {
+/// Removes `path`, returning whether nothing inside it had to be left
+/// behind because it failed.
+fn remove_path(path: &str, args: &Args, progress: &mut Progress) -> Result<bool> {
     let path_obj = Path::new(path);
     
-    if !path_obj.exists() {
+    // Look at the link itself, so a symlink to a directory is removed
+    // rather than walked, and a dangling one can still be removed
+    let Ok(metadata) = fs::symlink_metadata(path_obj) else {
         if args.force {
-            return Ok(()); // Silently succeed with -f flag
+            return Ok(true); // Silently succeed with -f flag
         }
         anyhow::bail!("cannot remove '{}': No such file or directory", path);
-    }
+    };
     
-    if path_obj.is_dir() {
+    if metadata.is_dir() {
         if args.recursive {
             // Recursively remove directory and contents
-            fs::remove_dir_all(path_obj)?;
-            
-            if args.verbose {
-                println!("removed directory '{}'", path);
-            }
+            let device = if args.one_file_system {
+                device_id(&metadata)
+            } else {
+                None
+            };
+            let mut failed = false;
+            remove_tree(path_obj, args, device, progress, &mut failed)?;
+            return Ok(!failed);
         } else if args.dir {
+            if !approved(path_obj, &metadata, args)? {
+                return Ok(true);
+            }
             // Remove empty directory only
             match fs::remove_dir(path_obj) {
                 Ok(_) => {
                     if args.verbose {
                         println!("removed directory '{}'", path);
                     }
+                    progress.record();
                 }
                 Err(_) => {
                     anyhow::bail!("cannot remove '{}': Directory not empty", path);
@@ -81,21 +267,397 @@ fn remove_path(path: &str, args: &Args) -> Result<()> {
             anyhow::bail!("cannot remove '{}': Is a directory", path);
         }
     } else {
+        if !approved(path_obj, &metadata, args)? {
+            return Ok(true);
+        }
         // Remove file
         fs::remove_file(path_obj)?;
         
         if args.verbose {
             println!("removed '{}'", path);
         }
+        progress.record();
+    }
+    
+    Ok(true)
+}
+
+/// Whether `path` is the root directory, so that `/.` and `/tmp/..` are
+/// caught as well as `/` itself. A symlink to `/` is not: removing it
+/// only unlinks the link.
+#[cfg(unix)]
+fn is_root(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fs::symlink_metadata(path), fs::symlink_metadata("/")) {
+        (Ok(metadata), Ok(root)) => metadata.dev() == root.dev() && metadata.ino() == root.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_root(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|metadata| !metadata.is_symlink())
+        && fs::canonicalize(path).is_ok_and(|path| path.parent().is_none())
+}
+
+/// Where `--trash` puts things: a directory for the files themselves and,
+/// in a freedesktop.org trash, one for the `.trashinfo` records saying
+/// where each came from and when it was deleted.
+struct Trash {
+    files: PathBuf,
+    info: Option<PathBuf>,
+}
+
+impl Trash {
+    fn create(&self) -> Result<()> {
+        for dir in std::iter::once(&self.files).chain(&self.info) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("cannot create trash directory '{}'", dir.display()))?;
+        }
+        Ok(())
+    }
+    
+    /// The `.trashinfo` record for a file called `name` in the trash.
+    fn info_path(&self, name: &OsStr) -> Option<PathBuf> {
+        self.info.as_ref().map(|info| {
+            let mut record = name.to_os_string();
+            record.push(".trashinfo");
+            info.join(record)
+        })
+    }
+}
+
+/// `RUST_CLI_TRASH` if set, used as a plain directory without
+/// `.trashinfo` records; otherwise the platform's trash.
+fn trash_directory() -> Result<Trash> {
+    if let Some(dir) = env::var_os("RUST_CLI_TRASH").filter(|dir| !dir.is_empty()) {
+        return Ok(Trash {
+            files: PathBuf::from(dir),
+            info: None,
+        });
+    }
+    
+    platform_trash()
+}
+
+/// The freedesktop.org trash under `$XDG_DATA_HOME`, which defaults to
+/// `~/.local/share`.
+#[cfg(not(any(target_os = "macos", windows)))]
+fn platform_trash() -> Result<Trash> {
+    let data_home = match env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => home_directory()?.join(".local/share"),
+    };
+    
+    let trash = data_home.join("Trash");
+    Ok(Trash {
+        files: trash.join("files"),
+        info: Some(trash.join("info")),
+    })
+}
+
+/// `~/.Trash`. The Finder keeps its own record of where files came from,
+/// which isn't written here, so "Put Back" won't work on them.
+#[cfg(target_os = "macos")]
+fn platform_trash() -> Result<Trash> {
+    Ok(Trash {
+        files: home_directory()?.join(".Trash"),
+        info: None,
+    })
+}
+
+#[cfg(windows)]
+fn platform_trash() -> Result<Trash> {
+    anyhow::bail!("the Recycle Bin is not supported yet; set RUST_CLI_TRASH to a directory to use instead")
+}
+
+#[cfg(not(windows))]
+fn home_directory() -> Result<PathBuf> {
+    env::var_os("HOME")
+        .map(PathBuf::from)
+        .context("cannot locate the trash: neither RUST_CLI_TRASH nor HOME is set")
+}
+
+/// Moves `path` into `trash`, applying the same directory rules as a
+/// real removal.
+///
+/// If `path` is on another file system and can't be copied over, it is
+/// removed for good instead, with a warning.
+fn move_to_trash(path: &str, args: &Args, trash: &Trash, progress: &mut Progress) -> Result<bool> {
+    let path_obj = Path::new(path);
+    
+    let Ok(metadata) = fs::symlink_metadata(path_obj) else {
+        if args.force {
+            return Ok(true);
+        }
+        anyhow::bail!("cannot remove '{}': No such file or directory", path);
+    };
+    
+    if metadata.is_dir() && !args.recursive {
+        if !args.dir {
+            anyhow::bail!("cannot remove '{}': Is a directory", path);
+        }
+        // -d only takes empty directories, as it does without --trash
+        if fs::read_dir(path_obj)?.next().is_some() {
+            anyhow::bail!("cannot remove '{}': Directory not empty", path);
+        }
+    }
+    
+    if !approved(path_obj, &metadata, args)? {
+        return Ok(true);
+    }
+    
+    let name = path_obj
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("cannot move '{}' to the trash", path))?;
+    let now = common::time::unix_seconds(SystemTime::now());
+    let destination = trash_destination(trash, name, now);
+    
+    // The record goes first, claiming the name, as the spec asks
+    let info = destination.file_name().and_then(|name| trash.info_path(name));
+    if let Some(info) = &info {
+        write_trash_info(info, path_obj, now)?;
+    }
+    
+    if let Err(e) = common::fs::move_path(path_obj, &destination) {
+        if let Some(info) = &info {
+            let _ = fs::remove_file(info);
+        }
+        
+        let trash_device = fs::metadata(&trash.files).ok().and_then(|trash| device_id(&trash));
+        if !on_other_device(device_id(&metadata), trash_device) {
+            return Err(e).with_context(|| format!("cannot move '{}' to the trash", path));
+        }
+        
+        eprintln!(
+            "rm: warning: cannot move '{}' to the trash on another file system ({}); removing it instead",
+            path, e
+        );
+        // The user has already been asked, if -i wanted them to be
+        let args = Args {
+            interactive: false,
+            ..args.clone()
+        };
+        return remove_path(path, &args, progress);
+    }
+    
+    if args.verbose {
+        println!("trashed '{}' to '{}'", path, destination.display());
+    }
+    progress.record();
+    
+    Ok(true)
+}
+
+/// Writes the `.trashinfo` record for `path`, deleted at `now`. The file
+/// must not exist yet, so two removals can't claim the same name.
+fn write_trash_info(info: &Path, path: &Path, now: i64) -> Result<()> {
+    let original = std::path::absolute(path)?;
+    let (offset, _) = common::time::local_zone(now);
+    let record = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        percent_encode(original.as_os_str()),
+        common::time::format_datetime(now + offset)
+    );
+    
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(info)
+        .and_then(|mut file| file.write_all(record.as_bytes()))
+        .with_context(|| format!("cannot write '{}'", info.display()))
+}
+
+/// Percent-encodes a path for a `.trashinfo` record, leaving `/` and the
+/// characters a URI never needs to escape as they are.
+fn percent_encode(path: &OsStr) -> String {
+    path.as_encoded_bytes()
+        .iter()
+        .map(|&byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                char::from(byte).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Picks a free name for `name` in `trash`: the name itself if nothing
+/// has it yet, else the name with the deletion time appended, and then a
+/// counter after that. A name is only free if its `.trashinfo` record is
+/// too.
+fn trash_destination(trash: &Trash, name: &OsStr, now: i64) -> PathBuf {
+    let unused = |path: &Path| fs::symlink_metadata(path).is_err();
+    let is_free = |name: &OsString| {
+        unused(&trash.files.join(name)) && trash.info_path(name).is_none_or(|info| unused(&info))
+    };
+    
+    let name = name.to_os_string();
+    if is_free(&name) {
+        return trash.files.join(name);
+    }
+    
+    let stamped = format!("{}.{}", name.to_string_lossy(), now);
+    let mut candidate = OsString::from(&stamped);
+    let mut counter = 0;
+    while !is_free(&candidate) {
+        counter += 1;
+        candidate = format!("{}-{}", stamped, counter).into();
+    }
+    
+    trash.files.join(candidate)
+}
+
+/// Removes `path` and everything under it, depth first. Symlinks inside
+/// the tree are removed, never followed.
+///
+/// When `device` is given, for `--one-file-system`, directories on any
+/// other device are skipped with a warning rather than entered.
+///
+/// With `-i`, each directory is only entered and each entry only removed
+/// once the user agrees. Returns whether `path` itself was removed, which
+/// it can't be if anything under it was kept.
+///
+/// An entry that can't be removed is reported on stderr, like GNU rm,
+/// and its siblings are still removed; `failed` records that it happened.
+fn remove_tree(
+    path: &Path,
+    args: &Args,
+    device: Option<u64>,
+    progress: &mut Progress,
+    failed: &mut bool,
+) -> Result<bool> {
+    if args.interactive
+        && !confirm(&format!("descend into directory '{}'?", path.display()))?
+    {
+        return Ok(false);
+    }
+    
+    let entries = fs::read_dir(path)
+        .with_context(|| format!("cannot read directory '{}'", path.display()))?;
+    let mut emptied = true;
+    
+    for entry in entries {
+        let result = entry
+            .with_context(|| format!("cannot read directory '{}'", path.display()))
+            .and_then(|entry| remove_entry(&entry, args, device, progress, failed));
+        match result {
+            Ok(removed) => emptied &= removed,
+            Err(e) => {
+                common::exit::report("rm", Err::<(), _>(e));
+                *failed = true;
+                emptied = false;
+            }
+        }
+    }
+    
+    if !emptied || !approved(path, &fs::symlink_metadata(path)?, args)? {
+        return Ok(false);
+    }
+    
+    fs::remove_dir(path)
+        .with_context(|| format!("cannot remove directory '{}'", path.display()))?;
+    if args.verbose {
+        println!("removed directory '{}'", path.display());
+    }
+    progress.record();
+    
+    Ok(true)
+}
+
+/// Removes one entry found by [`remove_tree`], returning whether it went.
+fn remove_entry(
+    entry: &fs::DirEntry,
+    args: &Args,
+    device: Option<u64>,
+    progress: &mut Progress,
+    failed: &mut bool,
+) -> Result<bool> {
+    let child = entry.path();
+    let metadata = entry
+        .metadata()
+        .with_context(|| format!("cannot remove '{}'", child.display()))?;
+    
+    if metadata.is_dir() && on_other_device(device, device_id(&metadata)) {
+        eprintln!(
+            "rm: skipping '{}', since it's on a different device",
+            child.display()
+        );
+        return Ok(false);
+    }
+    if metadata.is_dir() {
+        return remove_tree(&child, args, device, progress, failed);
+    }
+    if !approved(&child, &metadata, args)? {
+        return Ok(false);
+    }
+    
+    fs::remove_file(&child)
+        .with_context(|| format!("cannot remove '{}'", child.display()))?;
+    if args.verbose {
+        println!("removed '{}'", child.display());
+    }
+    progress.record();
+    
+    Ok(true)
+}
+
+/// The device a file lives on, where the platform can tell.
+#[cfg(unix)]
+fn device_id(metadata: &fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
+}
+
+#[cfg(not(unix))]
+fn device_id(_metadata: &fs::Metadata) -> Option<u64> {
+    None
+}
+
+/// Whether a file on `device` lies outside the file system `--one-file-system`
+/// keeps to. Nothing does when either device is unknown.
+fn on_other_device(root: Option<u64>, device: Option<u64>) -> bool {
+    matches!((root, device), (Some(root), Some(device)) if root != device)
+}
+
+/// Whether `path` may be removed: always, unless `-i` asks first.
+fn approved(path: &Path, metadata: &fs::Metadata, args: &Args) -> Result<bool> {
+    if !args.interactive {
+        return Ok(true);
     }
+    confirm(&format!("remove {} '{}'?", describe(metadata), path.display()))
+}
+
+/// Names the kind of file in a prompt, the way GNU rm does.
+fn describe(metadata: &fs::Metadata) -> &'static str {
+    let file_type = metadata.file_type();
+    if file_type.is_dir() {
+        "directory"
+    } else if file_type.is_symlink() {
+        "symbolic link"
+    } else if file_type.is_file() && metadata.len() == 0 {
+        "regular empty file"
+    } else if file_type.is_file() {
+        "regular file"
+    } else {
+        "file"
+    }
+}
+
+/// Asks `question` on stderr and reads the answer from stdin. Only an
+/// answer starting with `y` or `Y` counts as yes.
+fn confirm(question: &str) -> Result<bool> {
+    eprint!("rm: {} ", question);
+    io::stderr().flush()?;
     
-    Ok(())
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim_start().starts_with(['y', 'Y']))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::env;
     use std::fs::File;
 
     #[test]
@@ -109,12 +671,20 @@ mod tests {
         let args = Args {
             recursive: false,
             force: false,
+            interactive: false,
+            interactive_once: false,
             verbose: false,
             dir: false,
+            preserve_root: false,
+            no_preserve_root: false,
+            one_file_system: false,
+            glob: false,
+            progress: false,
+            trash: false,
             files: vec![],
         };
         
-        let result = remove_path(test_file.to_str().unwrap(), &args);
+        let result = remove_path(test_file.to_str().unwrap(), &args, &mut Progress::new(false));
         assert!(result.is_ok());
         assert!(!test_file.exists());
     }
@@ -129,12 +699,20 @@ mod tests {
         let args = Args {
             recursive: false,
             force: false,
+            interactive: false,
+            interactive_once: false,
             verbose: false,
             dir: false,
+            preserve_root: false,
+            no_preserve_root: false,
+            one_file_system: false,
+            glob: false,
+            progress: false,
+            trash: false,
             files: vec![],
         };
         
-        let result = remove_path(test_dir.to_str().unwrap(), &args);
+        let result = remove_path(test_dir.to_str().unwrap(), &args, &mut Progress::new(false));
         assert!(result.is_err());
         
         // Cleanup
@@ -152,12 +730,20 @@ mod tests {
         let args = Args {
             recursive: true,
             force: false,
+            interactive: false,
+            interactive_once: false,
             verbose: false,
             dir: false,
+            preserve_root: false,
+            no_preserve_root: false,
+            one_file_system: false,
+            glob: false,
+            progress: false,
+            trash: false,
             files: vec![],
         };
         
-        let result = remove_path(test_dir.to_str().unwrap(), &args);
+        let result = remove_path(test_dir.to_str().unwrap(), &args, &mut Progress::new(false));
         assert!(result.is_ok());
         assert!(!test_dir.exists());
     }
@@ -167,13 +753,201 @@ mod tests {
         let args = Args {
             recursive: false,
             force: true,
+            interactive: false,
+            interactive_once: false,
             verbose: false,
             dir: false,
+            preserve_root: false,
+            no_preserve_root: false,
+            one_file_system: false,
+            glob: false,
+            progress: false,
+            trash: false,
             files: vec![],
         };
         
-        let result = remove_path("/nonexistent_file_12345.txt", &args);
+        let result = remove_path("/nonexistent_file_12345.txt", &args, &mut Progress::new(false));
         assert!(result.is_ok()); // Should succeed with -f flag
     }
+
+    #[test]
+    fn test_progress_counts_every_removed_entry() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("tree");
+        fs::create_dir_all(root.join("sub/deeper")).unwrap();
+        File::create(root.join("a.txt")).unwrap();
+        File::create(root.join("sub/b.txt")).unwrap();
+        File::create(root.join("sub/deeper/c.txt")).unwrap();
+        
+        let args = Args {
+            recursive: true,
+            force: false,
+            interactive: false,
+            interactive_once: false,
+            verbose: false,
+            dir: false,
+            preserve_root: false,
+            no_preserve_root: false,
+            one_file_system: false,
+            glob: false,
+            progress: true,
+            trash: false,
+            files: vec![],
+        };
+        
+        let mut progress = Progress::new(true);
+        remove_path(root.to_str().unwrap(), &args, &mut progress).unwrap();
+        
+        // Three files and three directories, the root included
+        assert_eq!(progress.removed, 6);
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn test_trash_destination_avoids_collisions() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let files = temp_dir.path().join("files");
+        let info = temp_dir.path().join("info");
+        fs::create_dir(&files).unwrap();
+        fs::create_dir(&info).unwrap();
+        let trash = Trash {
+            files: files.clone(),
+            info: Some(info.clone()),
+        };
+        let name = OsStr::new("notes.txt");
+        
+        assert_eq!(trash_destination(&trash, name, 1700), files.join("notes.txt"));
+        
+        File::create(files.join("notes.txt")).unwrap();
+        assert_eq!(trash_destination(&trash, name, 1700), files.join("notes.txt.1700"));
+        
+        // A leftover record takes the name as well
+        File::create(info.join("notes.txt.1700.trashinfo")).unwrap();
+        assert_eq!(trash_destination(&trash, name, 1700), files.join("notes.txt.1700-1"));
+    }
+
+    #[test]
+    fn test_trash_with_d_only_takes_empty_directories() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let trash = Trash {
+            files: temp_dir.path().join("trash"),
+            info: None,
+        };
+        trash.create().unwrap();
+        let full = temp_dir.path().join("full");
+        let empty = temp_dir.path().join("empty");
+        fs::create_dir(&full).unwrap();
+        File::create(full.join("file.txt")).unwrap();
+        fs::create_dir(&empty).unwrap();
+        
+        let args = Args {
+            dir: true,
+            trash: true,
+            ..Args::parse_from(["rm", "placeholder"])
+        };
+        let mut progress = Progress::new(false);
+        
+        let error = move_to_trash(full.to_str().unwrap(), &args, &trash, &mut progress).unwrap_err();
+        assert!(error.to_string().contains("Directory not empty"));
+        assert!(full.join("file.txt").exists());
+        
+        move_to_trash(empty.to_str().unwrap(), &args, &trash, &mut progress).unwrap();
+        assert!(!empty.exists());
+        assert!(trash.files.join("empty").is_dir());
+    }
+
+    #[test]
+    fn test_percent_encode_keeps_slashes() {
+        assert_eq!(percent_encode(OsStr::new("/home/me/a b.txt")), "/home/me/a%20b.txt");
+        assert_eq!(percent_encode(OsStr::new("/tmp/50%~")), "/tmp/50%25~");
+        assert_eq!(percent_encode(OsStr::new("/tmp/é")), "/tmp/%C3%A9");
+    }
+
+    #[test]
+    fn test_is_root_matches_the_root_directory() {
+        assert!(is_root(Path::new("/")));
+        assert!(is_root(Path::new("/.")));
+        assert!(is_root(Path::new("/tmp/..")));
+        assert!(!is_root(&env::temp_dir()));
+        assert!(!is_root(Path::new("/nonexistent_dir_12345")));
+    }
+
+    #[test]
+    fn test_on_other_device() {
+        assert!(!on_other_device(Some(1), Some(1)));
+        assert!(on_other_device(Some(1), Some(2)));
+        assert!(!on_other_device(None, Some(2)));
+        assert!(!on_other_device(Some(1), None));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_one_file_system_removes_tree_on_same_device() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("tree");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        File::create(root.join("sub/file.txt")).unwrap();
+        
+        let args = Args {
+            recursive: true,
+            one_file_system: true,
+            ..Args::parse_from(["rm", "placeholder"])
+        };
+        remove_path(root.to_str().unwrap(), &args, &mut Progress::new(false)).unwrap();
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn test_describe_names_file_kinds() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let empty = temp_dir.path().join("empty");
+        let full = temp_dir.path().join("full");
+        File::create(&empty).unwrap();
+        fs::write(&full, "data").unwrap();
+        
+        let kind = |path: &Path| describe(&fs::symlink_metadata(path).unwrap());
+        assert_eq!(kind(&empty), "regular empty file");
+        assert_eq!(kind(&full), "regular file");
+        assert_eq!(kind(temp_dir.path()), "directory");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_recursive_remove_does_not_follow_symlinks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let target = temp_dir.path().join("target");
+        fs::create_dir(&target).unwrap();
+        File::create(target.join("keep.txt")).unwrap();
+        
+        let tree = temp_dir.path().join("tree");
+        fs::create_dir(&tree).unwrap();
+        std::os::unix::fs::symlink(&target, tree.join("link")).unwrap();
+        std::os::unix::fs::symlink(&target, temp_dir.path().join("top-link")).unwrap();
+        
+        let args = Args {
+            recursive: true,
+            force: false,
+            interactive: false,
+            interactive_once: false,
+            verbose: false,
+            dir: false,
+            preserve_root: false,
+            no_preserve_root: false,
+            one_file_system: false,
+            glob: false,
+            progress: false,
+            trash: false,
+            files: vec![],
+        };
+        
+        let mut progress = Progress::new(false);
+        remove_path(tree.to_str().unwrap(), &args, &mut progress).unwrap();
+        let top_link = temp_dir.path().join("top-link");
+        remove_path(top_link.to_str().unwrap(), &args, &mut progress).unwrap();
+        
+        assert!(!tree.exists());
+        assert!(fs::symlink_metadata(&top_link).is_err());
+        assert!(target.join("keep.txt").exists());
+    }
 }
 
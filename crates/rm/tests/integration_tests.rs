@@ -0,0 +1,317 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_rm_trash_moves_file_into_trash() {
+    let temp_dir = TempDir::new().unwrap();
+    let trash = temp_dir.path().join("trash");
+    let file = temp_dir.path().join("notes.txt");
+    fs::write(&file, "keep me").unwrap();
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.env("RUST_CLI_TRASH", &trash).arg("--trash").arg(&file);
+    cmd.assert().success();
+
+    assert!(!file.exists());
+    assert_eq!(
+        fs::read_to_string(trash.join("notes.txt")).unwrap(),
+        "keep me"
+    );
+}
+
+#[test]
+fn test_rm_trash_keeps_both_copies_of_a_name() {
+    let temp_dir = TempDir::new().unwrap();
+    let trash = temp_dir.path().join("trash");
+    let file = temp_dir.path().join("notes.txt");
+
+    for content in ["first", "second"] {
+        fs::write(&file, content).unwrap();
+        let mut cmd = Command::cargo_bin("rm").unwrap();
+        cmd.env("RUST_CLI_TRASH", &trash).arg("--trash").arg(&file);
+        cmd.assert().success();
+    }
+
+    let mut contents: Vec<String> = fs::read_dir(&trash)
+        .unwrap()
+        .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect();
+    contents.sort();
+    assert_eq!(contents, ["first", "second"]);
+}
+
+#[test]
+fn test_rm_trash_directory_needs_recursive() {
+    let temp_dir = TempDir::new().unwrap();
+    let trash = temp_dir.path().join("trash");
+    let dir = temp_dir.path().join("project");
+    fs::create_dir(&dir).unwrap();
+    fs::write(dir.join("file.txt"), "data").unwrap();
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.env("RUST_CLI_TRASH", &trash).arg("--trash").arg(&dir);
+    cmd.assert().failure();
+    assert!(dir.exists());
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.env("RUST_CLI_TRASH", &trash)
+        .arg("--trash")
+        .arg("-r")
+        .arg(&dir);
+    cmd.assert().success();
+    assert!(!dir.exists());
+    assert!(trash.join("project/file.txt").exists());
+}
+
+#[test]
+fn test_rm_i_keeps_file_unless_confirmed() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("notes.txt");
+    fs::write(&file, "data").unwrap();
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.arg("-i").arg(&file).write_stdin("n\n");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("remove regular file"));
+    assert!(file.exists());
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.arg("-i").arg(&file).write_stdin("y\n");
+    cmd.assert().success();
+    assert!(!file.exists());
+}
+
+#[test]
+fn test_rm_ri_prompts_for_each_entry() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path().join("project");
+    fs::create_dir(&dir).unwrap();
+    fs::write(dir.join("file.txt"), "data").unwrap();
+
+    // Descend, but keep the file, so the directory has to stay too
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.arg("-ri").arg(&dir).write_stdin("y\nn\n");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("descend into directory"));
+    assert!(dir.join("file.txt").exists());
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.arg("-ri").arg(&dir).write_stdin("y\ny\ny\n");
+    cmd.assert().success();
+    assert!(!dir.exists());
+}
+
+#[test]
+fn test_rm_big_i_prompts_once_for_many_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let files: Vec<_> = (0..4)
+        .map(|n| temp_dir.path().join(format!("{}.txt", n)))
+        .collect();
+    for file in &files {
+        fs::write(file, "data").unwrap();
+    }
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.arg("-I").args(&files).write_stdin("n\n");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("remove 4 arguments?"));
+    assert!(files.iter().all(|file| file.exists()));
+
+    // Three files or fewer go without asking
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.arg("-I").args(&files[..3]);
+    cmd.assert().success().stderr("");
+    assert!(files[..3].iter().all(|file| !file.exists()));
+}
+
+#[test]
+fn test_rm_f_after_i_skips_prompt() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("notes.txt");
+    fs::write(&file, "data").unwrap();
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.arg("-i").arg("-f").arg(&file);
+    cmd.assert().success().stderr("");
+    assert!(!file.exists());
+}
+
+#[test]
+fn test_rm_rv_lists_every_entry() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path().join("project");
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::write(dir.join("sub/file.txt"), "data").unwrap();
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.arg("-rv").arg(&dir);
+    let expected = format!(
+        "removed '{0}/sub/file.txt'\nremoved directory '{0}/sub'\nremoved directory '{0}'\n",
+        dir.display()
+    );
+    cmd.assert().success().stdout(expected);
+    assert!(!dir.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_rm_preserve_root_allows_a_link_to_root() {
+    // Removing the link only unlinks it, so the guard leaves it alone
+    let temp_dir = TempDir::new().unwrap();
+    let link = temp_dir.path().join("root");
+    std::os::unix::fs::symlink("/", &link).unwrap();
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.arg("-rf").arg(&link);
+    cmd.assert().success();
+    assert!(fs::symlink_metadata(&link).is_err());
+    assert!(std::path::Path::new("/").is_dir());
+}
+
+#[test]
+fn test_rm_keeps_going_after_a_failure() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first.txt");
+    let missing = temp_dir.path().join("missing.txt");
+    let last = temp_dir.path().join("last.txt");
+    fs::write(&first, "data").unwrap();
+    fs::write(&last, "data").unwrap();
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.arg(&first).arg(&missing).arg(&last);
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("No such file or directory"));
+    assert!(!first.exists());
+    assert!(!last.exists());
+}
+
+#[test]
+fn test_rm_f_still_reports_real_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path().join("dir");
+    fs::create_dir(&dir).unwrap();
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.arg("-f").arg(&dir).arg(temp_dir.path().join("missing.txt"));
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("Is a directory"));
+    assert!(dir.exists());
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn test_rm_trash_writes_trashinfo() {
+    let temp_dir = TempDir::new().unwrap();
+    let data_home = temp_dir.path().join("data");
+    let file = temp_dir.path().join("my notes.txt");
+    fs::write(&file, "keep me").unwrap();
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.env_remove("RUST_CLI_TRASH")
+        .env("XDG_DATA_HOME", &data_home)
+        .arg("--trash")
+        .arg(&file);
+    cmd.assert().success();
+
+    let trash = data_home.join("Trash");
+    assert!(!file.exists());
+    assert_eq!(
+        fs::read_to_string(trash.join("files/my notes.txt")).unwrap(),
+        "keep me"
+    );
+
+    let info = fs::read_to_string(trash.join("info/my notes.txt.trashinfo")).unwrap();
+    let lines: Vec<&str> = info.lines().collect();
+    assert_eq!(lines[0], "[Trash Info]");
+    assert_eq!(
+        lines[1],
+        format!("Path={}/my%20notes.txt", file.parent().unwrap().display())
+    );
+    let date = lines[2].strip_prefix("DeletionDate=").unwrap();
+    assert_eq!(date.len(), "2024-02-29T13:45:10".len());
+    assert_eq!(&date[10..11], "T");
+}
+
+#[test]
+fn test_rm_glob_expands_patterns() {
+    let temp_dir = TempDir::new().unwrap();
+    for name in ["a.tmp", "b.tmp", "keep.txt"] {
+        fs::write(temp_dir.path().join(name), "data").unwrap();
+    }
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("--glob").arg("-v").arg("*.tmp");
+    cmd.assert()
+        .success()
+        .stdout("removed 'a.tmp'\nremoved 'b.tmp'\n");
+    assert!(temp_dir.path().join("keep.txt").exists());
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn test_rm_glob_reports_unmatched_patterns() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("--glob").arg("*.none");
+    cmd.assert()
+        .code(1)
+        .stderr("rm: cannot remove '*.none': No such file or directory\n");
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("--glob")
+        .arg("-f")
+        .arg("*.none");
+    cmd.assert().success().stderr("");
+}
+
+#[test]
+fn test_rm_without_glob_takes_patterns_literally() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("a.tmp"), "data").unwrap();
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("*.tmp");
+    cmd.assert().code(1);
+    assert!(temp_dir.path().join("a.tmp").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_rm_r_keeps_removing_past_a_failed_entry() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let tree = temp_dir.path().join("tree");
+    let locked = tree.join("locked");
+    fs::create_dir_all(&locked).unwrap();
+    fs::write(tree.join("a.txt"), "data").unwrap();
+    fs::write(locked.join("inner.txt"), "data").unwrap();
+    fs::write(tree.join("z.txt"), "data").unwrap();
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o555)).unwrap();
+
+    // Privileged users can write to the locked directory anyway
+    if fs::write(locked.join("probe"), "").is_ok() {
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        return;
+    }
+
+    let mut cmd = Command::cargo_bin("rm").unwrap();
+    cmd.arg("-r").arg(&tree);
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("inner.txt"));
+    assert!(locked.join("inner.txt").exists());
+    assert!(!tree.join("a.txt").exists());
+    assert!(!tree.join("z.txt").exists());
+
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+}
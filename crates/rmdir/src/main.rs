@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
 use clap::Parser;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::process::ExitCode;
 
 #[derive(Parser, Debug)]
 #[command(name = "rmdir")]
@@ -25,7 +27,11 @@ struct Args {
     directories: Vec<String>,
 }
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
+    common::exit::report("rmdir", run())
+}
+
+fn run() -> Result<()> {
     let args = Args::parse();
     
     for dir in &args.directories {
@@ -43,7 +49,7 @@ fn main() -> Result<()> {
 }
 
 fn remove_directory(path: &str, remove_parents: bool, verbose: bool) -> Result<()> {
-    let path_obj = Path::new(path);
+    let path_obj = &normalize(path);
     
     if !path_obj.exists() {
         anyhow::bail!("failed to remove '{}': No such file or directory", path);
@@ -66,19 +72,48 @@ fn remove_directory(path: &str, remove_parents: bool, verbose: bool) -> Result<(
         println!("removed directory '{}'", path);
     }
     
-    // If -p flag, try to remove parent directories
     if remove_parents {
-        if let Some(parent) = path_obj.parent() {
-            if parent.as_os_str().is_empty() || parent == Path::new(".") {
-                return Ok(());
+        remove_empty_parents(path_obj, verbose)?;
+    }
+    
+    Ok(())
+}
+
+/// `path` without trailing slashes or `.` components, so that `a/b/` and
+/// `./a/./b` both become `a/b`. A path of only `.` components stays `.`.
+fn normalize(path: &str) -> PathBuf {
+    let normalized: PathBuf = Path::new(path)
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .collect();
+    
+    if normalized.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        normalized
+    }
+}
+
+/// Removes the parents of `path` for `-p`, innermost first. The walk
+/// stops quietly at the first parent that still has something else in
+/// it, and before `..` or the root; any other failure is an error.
+fn remove_empty_parents(path: &Path, verbose: bool) -> Result<()> {
+    for parent in path.ancestors().skip(1) {
+        if !matches!(parent.components().next_back(), Some(Component::Normal(_))) {
+            break;
+        }
+        
+        match fs::remove_dir(parent) {
+            Ok(()) => {
+                if verbose {
+                    println!("removed directory '{}'", parent.display());
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::DirectoryNotEmpty => break,
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("failed to remove '{}'", parent.display()))
             }
-            
-            let parent_str = parent.to_str().ok_or_else(|| {
-                anyhow::anyhow!("Parent path contains invalid UTF-8")
-            })?;
-            
-            // Try to remove parent, but don't fail if it's not empty
-            let _ = remove_directory(parent_str, true, verbose);
         }
     }
     
@@ -119,6 +154,28 @@ mod tests {
         fs::remove_dir_all(&test_dir).unwrap();
     }
 
+    #[test]
+    fn test_parents_stop_at_a_shared_directory() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let top = temp_dir.path().join("a");
+        fs::create_dir_all(top.join("b/c")).unwrap();
+        fs::create_dir(top.join("other")).unwrap();
+        
+        remove_directory(top.join("b/c").to_str().unwrap(), true, false).unwrap();
+        
+        assert!(!top.join("b").exists());
+        assert!(top.join("other").exists());
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("a/b/"), Path::new("a/b"));
+        assert_eq!(normalize("./a/./b//"), Path::new("a/b"));
+        assert_eq!(normalize("/tmp/./a"), Path::new("/tmp/a"));
+        assert_eq!(normalize("../a"), Path::new("../a"));
+        assert_eq!(normalize("./"), Path::new("."));
+    }
+
     #[test]
     fn test_remove_nonexistent_directory_fails() {
         let result = remove_directory("/nonexistent_dir_12345", false, false);
@@ -0,0 +1,76 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_rmdir_pv_reports_each_parent() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("a/b/c")).unwrap();
+
+    let mut cmd = Command::cargo_bin("rmdir").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("-pv").arg("a/b/c");
+    cmd.assert().success().stdout(predicate::eq(
+        "removed directory 'a/b/c'\nremoved directory 'a/b'\nremoved directory 'a'\n",
+    ));
+    assert!(!temp_dir.path().join("a").exists());
+}
+
+#[test]
+fn test_rmdir_p_stops_at_a_shared_parent() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("a/b/c")).unwrap();
+    fs::create_dir(temp_dir.path().join("a/other")).unwrap();
+
+    let mut cmd = Command::cargo_bin("rmdir").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("-pv").arg("a/b/c");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq(
+            "removed directory 'a/b/c'\nremoved directory 'a/b'\n",
+        ))
+        .stderr("");
+    assert!(temp_dir.path().join("a/other").exists());
+}
+
+#[test]
+fn test_rmdir_trailing_slash() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("a/b")).unwrap();
+
+    let mut cmd = Command::cargo_bin("rmdir").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("a/b/");
+    cmd.assert().success();
+    assert!(!temp_dir.path().join("a/b").exists());
+    assert!(temp_dir.path().join("a").exists());
+}
+
+#[test]
+fn test_rmdir_leading_dot() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir(temp_dir.path().join("a")).unwrap();
+
+    let mut cmd = Command::cargo_bin("rmdir").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("./a");
+    cmd.assert().success();
+    assert!(!temp_dir.path().join("a").exists());
+}
+
+#[test]
+fn test_rmdir_p_never_removes_dot() {
+    let temp_dir = TempDir::new().unwrap();
+    let work = temp_dir.path().join("work");
+    fs::create_dir_all(work.join("a/b")).unwrap();
+
+    // Once a and b are gone, work is empty, but it is '.' here and must stay
+    let mut cmd = Command::cargo_bin("rmdir").unwrap();
+    cmd.current_dir(&work).arg("-pv").arg("./a/./b/");
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq(
+            "removed directory './a/./b/'\nremoved directory 'a'\n",
+        ))
+        .stderr("");
+    assert!(work.exists());
+    assert!(!work.join("a").exists());
+}
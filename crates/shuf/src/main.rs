@@ -0,0 +1,240 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use common::exit::UsageError;
+use common::io::{read_record, record_separator};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+
+#[derive(Parser, Debug)]
+#[command(name = "shuf")]
+#[command(about = "Write a random permutation of the input lines", long_about = None)]
+#[command(version)]
+struct Args {
+    /// Treat each operand as an input line
+    #[arg(short = 'e', long = "echo", conflicts_with = "input_range")]
+    echo: bool,
+
+    /// Treat each number LO through HI as an input line
+    #[arg(short = 'i', long = "input-range", value_name = "LO-HI")]
+    input_range: Option<String>,
+
+    /// Output at most COUNT lines
+    #[arg(short = 'n', long = "head-count", value_name = "COUNT")]
+    head_count: Option<usize>,
+
+    /// Output lines can be repeated (sampling with replacement)
+    #[arg(short = 'r', long = "repeat")]
+    repeat: bool,
+
+    /// Seed the shuffle from the first bytes of FILE, for repeatable output
+    #[arg(long = "random-source", value_name = "FILE")]
+    random_source: Option<String>,
+
+    /// Line delimiter is NUL, not newline
+    #[arg(short = 'z', long = "zero-terminated")]
+    zero_terminated: bool,
+
+    /// Input lines with -e, otherwise the input file (use '-' for stdin)
+    operands: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    common::exit::report("shuf", run())
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse();
+    let separator = record_separator(args.zero_terminated);
+
+    let lines = if args.echo {
+        args.operands
+            .iter()
+            .map(|s| s.clone().into_bytes())
+            .collect()
+    } else if let Some(range) = &args.input_range {
+        if !args.operands.is_empty() {
+            anyhow::bail!(UsageError::new(format!("extra operand '{}'", args.operands[0])));
+        }
+        parse_range(range)?
+            .map(|n| n.to_string().into_bytes())
+            .collect()
+    } else {
+        if args.operands.len() > 1 {
+            anyhow::bail!(UsageError::new(format!("extra operand '{}'", args.operands[1])));
+        }
+        let path = args.operands.first().map_or("-", String::as_str);
+        let mut reader = common::io::open_input(path)
+            .with_context(|| format!("Failed to open file: {}", path))?;
+        let mut lines = Vec::new();
+        while let Some(line) = read_record(&mut reader, separator)? {
+            lines.push(line);
+        }
+        lines
+    };
+
+    let mut rng = match &args.random_source {
+        Some(path) => seeded_rng(path)?,
+        None => ChaCha8Rng::from_entropy(),
+    };
+
+    let mut stdout = common::io::stdout_writer();
+    let result = if args.repeat {
+        if lines.is_empty() && args.head_count != Some(0) {
+            anyhow::bail!("no lines to repeat");
+        }
+        write_repeated(&lines, args.head_count, &mut rng, &mut stdout, separator)
+    } else {
+        write_shuffled(lines, args.head_count, &mut rng, &mut stdout, separator)
+    };
+
+    // A closed pipe (e.g. `shuf -r | head`) just ends the output
+    match result.and_then(|_| stdout.flush()) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        other => other.context("Failed to write output"),
+    }
+}
+
+/// Parses `LO-HI` into an inclusive range. `HI` may be one less than `LO`
+/// for an empty range.
+fn parse_range(range: &str) -> Result<std::ops::RangeInclusive<u64>> {
+    let invalid = || anyhow::Error::new(UsageError::new(format!("invalid input range: '{}'", range)));
+
+    let (low, high) = range.split_once('-').ok_or_else(invalid)?;
+    let low: u64 = low.parse().map_err(|_| invalid())?;
+    let high: u64 = high.parse().map_err(|_| invalid())?;
+
+    if high.checked_add(1).is_none_or(|end| end < low) {
+        return Err(invalid());
+    }
+
+    Ok(low..=high)
+}
+
+/// Builds a generator seeded from the first 32 bytes of `path`, padded
+/// with zeros if the file is shorter.
+fn seeded_rng(path: &str) -> Result<ChaCha8Rng> {
+    let mut seed = [0u8; 32];
+    let file = File::open(path).with_context(|| format!("Failed to open file: {}", path))?;
+
+    let mut filled = 0;
+    let mut limited = file.take(seed.len() as u64);
+    while filled < seed.len() {
+        let read = limited.read(&mut seed[filled..])?;
+        if read == 0 {
+            break;
+        }
+        filled += read;
+    }
+
+    Ok(ChaCha8Rng::from_seed(seed))
+}
+
+fn write_shuffled<R: Rng, W: Write>(
+    mut lines: Vec<Vec<u8>>,
+    count: Option<usize>,
+    rng: &mut R,
+    writer: &mut W,
+    separator: u8,
+) -> io::Result<()> {
+    let count = count.unwrap_or(lines.len()).min(lines.len());
+    let (chosen, _) = lines.partial_shuffle(rng, count);
+
+    for line in chosen {
+        writer.write_all(line)?;
+        writer.write_all(&[separator])?;
+    }
+
+    Ok(())
+}
+
+/// Writes `count` lines picked independently at random, or keeps going
+/// forever when no count is given.
+fn write_repeated<R: Rng, W: Write>(
+    lines: &[Vec<u8>],
+    count: Option<usize>,
+    rng: &mut R,
+    writer: &mut W,
+    separator: u8,
+) -> io::Result<()> {
+    let mut written = 0;
+
+    while count.is_none_or(|count| written < count) {
+        let Some(line) = lines.choose(rng) else {
+            break;
+        };
+        writer.write_all(line)?;
+        writer.write_all(&[separator])?;
+        written += 1;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range_lines(range: &str) -> Vec<Vec<u8>> {
+        parse_range(range)
+            .unwrap()
+            .map(|n| n.to_string().into_bytes())
+            .collect()
+    }
+
+    fn shuffled(lines: Vec<Vec<u8>>, count: Option<usize>, seed: u64) -> Vec<String> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut output = Vec::new();
+        write_shuffled(lines, count, &mut rng, &mut output, b'\n').unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_input_range_is_a_permutation() {
+        let mut output = shuffled(range_lines("1-5"), None, 7);
+        output.sort();
+        assert_eq!(output, ["1", "2", "3", "4", "5"]);
+    }
+
+    #[test]
+    fn test_head_count_limits_output() {
+        let output = shuffled(range_lines("1-5"), Some(2), 7);
+        assert_eq!(output.len(), 2);
+        assert_ne!(output[0], output[1]);
+
+        assert_eq!(shuffled(range_lines("1-3"), Some(10), 7).len(), 3);
+    }
+
+    #[test]
+    fn test_same_seed_same_order() {
+        let first = shuffled(range_lines("1-100"), None, 42);
+        let second = shuffled(range_lines("1-100"), None, 42);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_repeat_samples_with_replacement() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut output = Vec::new();
+        write_repeated(&range_lines("1-2"), Some(20), &mut rng, &mut output, b'\n').unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 20);
+        assert!(output.lines().all(|line| line == "1" || line == "2"));
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("3-7").unwrap(), 3..=7);
+        assert_eq!(parse_range("5-4").unwrap().count(), 0);
+        assert!(parse_range("5-3").is_err());
+        assert!(parse_range("a-3").is_err());
+        assert!(parse_range("7").is_err());
+    }
+}
@@ -0,0 +1,37 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+fn run(args: &[&str], stdin: &str) -> String {
+    let mut cmd = Command::cargo_bin("shuf").unwrap();
+    cmd.args(args).write_stdin(stdin);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn test_shuf_stdin_keeps_every_line() {
+    let mut lines: Vec<String> = run(&[], "a\nb\nc\nd\n").lines().map(String::from).collect();
+    lines.sort();
+    assert_eq!(lines, ["a", "b", "c", "d"]);
+}
+
+#[test]
+fn test_shuf_echo_with_head_count() {
+    let output = run(&["-e", "-n", "1", "x", "y", "z"], "");
+    assert_eq!(output.lines().count(), 1);
+    assert!(["x\n", "y\n", "z\n"].contains(&output.as_str()));
+}
+
+#[test]
+fn test_shuf_random_source_is_repeatable() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("seed");
+    fs::write(&source, "some fixed seed bytes").unwrap();
+    let source = source.to_str().unwrap();
+
+    let first = run(&["-i", "1-50", "--random-source", source], "");
+    let second = run(&["-i", "1-50", "--random-source", source], "");
+    assert_eq!(first, second);
+    assert_eq!(first.lines().count(), 50);
+}
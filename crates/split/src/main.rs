@@ -0,0 +1,218 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use common::exit::UsageError;
+use common::size::parse_size;
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Read, Write};
+use std::process::ExitCode;
+
+const BUFFER_SIZE: usize = 64 * 1024;
+
+#[derive(Parser, Debug)]
+#[command(name = "split")]
+#[command(about = "Split a file into pieces", long_about = None)]
+#[command(version)]
+struct Args {
+    /// Put N lines per output file
+    #[arg(short = 'l', long = "lines", conflicts_with = "bytes")]
+    lines: Option<u64>,
+
+    /// Put SIZE bytes per output file (e.g. 100, 10K, 1M)
+    #[arg(short = 'b', long = "bytes")]
+    bytes: Option<String>,
+
+    /// Use numeric suffixes instead of alphabetic
+    #[arg(short = 'd', long = "numeric-suffixes")]
+    numeric_suffixes: bool,
+
+    /// Length of the generated suffixes
+    #[arg(short = 'a', long = "suffix-length", default_value_t = 2)]
+    suffix_length: usize,
+
+    /// Input file (use '-' for stdin)
+    #[arg(default_value = "-")]
+    input: String,
+
+    /// Prefix for output file names
+    #[arg(default_value = "x")]
+    prefix: String,
+}
+
+fn main() -> ExitCode {
+    common::exit::report("split", run())
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse();
+
+    if args.suffix_length == 0 {
+        anyhow::bail!(UsageError::new("invalid suffix length: 0"));
+    }
+
+    let mut outputs = OutputFiles::new(&args.prefix, args.numeric_suffixes, args.suffix_length);
+    let reader = common::io::open_input(&args.input)
+        .with_context(|| format!("Failed to open file: {}", args.input))?;
+
+    if let Some(size) = &args.bytes {
+        let bytes = parse_size(size)?;
+        if bytes == 0 {
+            anyhow::bail!(UsageError::new(format!("invalid number of bytes: '{}'", size)));
+        }
+        split_by_bytes(reader, bytes, &mut outputs)?;
+    } else {
+        let lines = args.lines.unwrap_or(1000);
+        if lines == 0 {
+            anyhow::bail!(UsageError::new("invalid number of lines: 0"));
+        }
+        split_by_lines(reader, lines, &mut outputs)?;
+    }
+
+    Ok(())
+}
+
+/// Generates the sequence of output file names: `xaa`, `xab`, ... or
+/// `x00`, `x01`, ... with `-d`.
+struct OutputFiles {
+    prefix: String,
+    numeric: bool,
+    suffix_length: usize,
+    index: u64,
+}
+
+impl OutputFiles {
+    fn new(prefix: &str, numeric: bool, suffix_length: usize) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            numeric,
+            suffix_length,
+            index: 0,
+        }
+    }
+
+    fn next_name(&mut self) -> Result<String> {
+        let radix: u64 = if self.numeric { 10 } else { 26 };
+        let mut remaining = self.index;
+        let mut suffix = vec![0u8; self.suffix_length];
+
+        for slot in suffix.iter_mut().rev() {
+            let digit = (remaining % radix) as u8;
+            *slot = if self.numeric { b'0' + digit } else { b'a' + digit };
+            remaining /= radix;
+        }
+
+        if remaining > 0 {
+            anyhow::bail!("output file suffixes exhausted");
+        }
+
+        self.index += 1;
+        Ok(format!("{}{}", self.prefix, String::from_utf8_lossy(&suffix)))
+    }
+
+    fn create_next(&mut self) -> Result<BufWriter<File>> {
+        let name = self.next_name()?;
+        let file = File::create(&name).with_context(|| format!("Failed to create file: {}", name))?;
+        Ok(BufWriter::new(file))
+    }
+}
+
+fn split_by_lines<R: BufRead>(
+    mut reader: R,
+    lines_per_file: u64,
+    outputs: &mut OutputFiles,
+) -> Result<()> {
+    let mut line = Vec::new();
+    let mut current: Option<BufWriter<File>> = None;
+    let mut written = 0;
+
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        if current.is_none() || written == lines_per_file {
+            if let Some(mut writer) = current.take() {
+                writer.flush()?;
+            }
+            current = Some(outputs.create_next()?);
+            written = 0;
+        }
+
+        if let Some(writer) = current.as_mut() {
+            writer.write_all(&line)?;
+        }
+        written += 1;
+        line.clear();
+    }
+
+    if let Some(mut writer) = current {
+        writer.flush()?;
+    }
+
+    Ok(())
+}
+
+fn split_by_bytes<R: Read>(
+    mut reader: R,
+    bytes_per_file: u64,
+    outputs: &mut OutputFiles,
+) -> Result<()> {
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut current: Option<BufWriter<File>> = None;
+    let mut written = 0;
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+
+        let mut chunk = &buffer[..read];
+        while !chunk.is_empty() {
+            if current.is_none() || written == bytes_per_file {
+                if let Some(mut writer) = current.take() {
+                    writer.flush()?;
+                }
+                current = Some(outputs.create_next()?);
+                written = 0;
+            }
+
+            let take = chunk.len().min((bytes_per_file - written) as usize);
+            if let Some(writer) = current.as_mut() {
+                writer.write_all(&chunk[..take])?;
+            }
+            written += take as u64;
+            chunk = &chunk[take..];
+        }
+    }
+
+    if let Some(mut writer) = current {
+        writer.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alphabetic_suffixes() {
+        let mut outputs = OutputFiles::new("x", false, 2);
+        assert_eq!(outputs.next_name().unwrap(), "xaa");
+        assert_eq!(outputs.next_name().unwrap(), "xab");
+
+        outputs.index = 26;
+        assert_eq!(outputs.next_name().unwrap(), "xba");
+    }
+
+    #[test]
+    fn test_numeric_suffixes() {
+        let mut outputs = OutputFiles::new("part-", true, 3);
+        assert_eq!(outputs.next_name().unwrap(), "part-000");
+        assert_eq!(outputs.next_name().unwrap(), "part-001");
+    }
+
+    #[test]
+    fn test_suffixes_exhausted() {
+        let mut outputs = OutputFiles::new("x", true, 1);
+        outputs.index = 10;
+        assert!(outputs.next_name().is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+fn numbered_lines(count: usize) -> String {
+    (1..=count).map(|i| format!("line {}\n", i)).collect()
+}
+
+#[test]
+fn test_split_by_lines() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("input.txt"), numbered_lines(10)).unwrap();
+
+    let mut cmd = Command::cargo_bin("split").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("-l").arg("3").arg("input.txt");
+    cmd.assert().success();
+
+    let expected = [("xaa", 3), ("xab", 3), ("xac", 3), ("xad", 1)];
+    for (name, lines) in expected {
+        let content = fs::read_to_string(temp_dir.path().join(name)).unwrap();
+        assert_eq!(content.lines().count(), lines, "{} line count", name);
+    }
+    assert!(!temp_dir.path().join("xae").exists());
+
+    let rejoined: String = expected
+        .iter()
+        .map(|(name, _)| fs::read_to_string(temp_dir.path().join(name)).unwrap())
+        .collect();
+    assert_eq!(rejoined, numbered_lines(10));
+}
+
+#[test]
+fn test_split_by_bytes_with_prefix() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("input.bin"), "abcdefghij").unwrap();
+
+    let mut cmd = Command::cargo_bin("split").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("-b")
+        .arg("4")
+        .arg("input.bin")
+        .arg("part-");
+    cmd.assert().success();
+
+    assert_eq!(fs::read_to_string(temp_dir.path().join("part-aa")).unwrap(), "abcd");
+    assert_eq!(fs::read_to_string(temp_dir.path().join("part-ab")).unwrap(), "efgh");
+    assert_eq!(fs::read_to_string(temp_dir.path().join("part-ac")).unwrap(), "ij");
+}
+
+#[test]
+fn test_split_numeric_suffixes() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("split").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("-d").arg("-l").arg("2");
+    cmd.write_stdin(numbered_lines(3));
+    cmd.assert().success();
+
+    assert!(temp_dir.path().join("x00").exists());
+    assert!(temp_dir.path().join("x01").exists());
+    assert!(!temp_dir.path().join("x02").exists());
+}
+
+#[test]
+fn test_split_invalid_size() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("split").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("-b").arg("10Q");
+    cmd.write_stdin("data");
+    cmd.assert().code(2);
+
+    let mut cmd = Command::cargo_bin("split").unwrap();
+    cmd.current_dir(temp_dir.path()).arg("-l").arg("0");
+    cmd.write_stdin("data");
+    cmd.assert().code(2);
+}
@@ -1,7 +1,12 @@
 use anyhow::{Context, Result};
-use clap::Parser;
-use std::fs::{File, OpenOptions};
+use clap::{Parser, ValueEnum};
+use common::exit::UsageError;
+use common::time::{civil_from_days, days_from_civil, days_in_month, from_local, SECONDS_PER_DAY};
+use filetime::FileTime;
+use std::fs::{self, File};
 use std::path::Path;
+use std::process::ExitCode;
+use walkdir::WalkDir;
 
 #[derive(Parser, Debug)]
 #[command(name = "touch")]
@@ -12,41 +17,232 @@ struct Args {
     #[arg(short = 'c', long = "no-create")]
     no_create: bool,
 
+    /// Touch every file inside directory arguments, recursively
+    #[arg(short = 'R', long = "recursive")]
+    recursive: bool,
+
+    /// With -R, also update the timestamps of directories
+    #[arg(long = "include-dirs", requires = "recursive")]
+    include_dirs: bool,
+
+    /// Change only the access time
+    #[arg(short = 'a')]
+    access: bool,
+
+    /// Change only the modification time
+    #[arg(short = 'm')]
+    modification: bool,
+
+    /// Change only this time: atime (or access, use) or mtime (or modify)
+    #[arg(long = "time", value_name = "WORD", value_enum)]
+    time: Option<TimeWord>,
+
+    /// Use this time instead of now, as [[CC]YY]MMDDhhmm[.ss] in local time
+    #[arg(short = 't', value_name = "STAMP", conflicts_with = "date")]
+    stamp: Option<String>,
+
+    /// Use this time instead of now (@SECONDS, YYYY-MM-DD, or
+    /// YYYY-MM-DD HH:MM[:SS], optionally ending in Z)
+    #[arg(short = 'd', long = "date", value_name = "STRING")]
+    date: Option<String>,
+
+    /// Use this file's times instead of now
+    #[arg(
+        short = 'r',
+        long = "reference",
+        value_name = "FILE",
+        conflicts_with_all = ["stamp", "date"]
+    )]
+    reference: Option<String>,
+
     /// Files to create or update
     #[arg(required = true)]
     files: Vec<String>,
 }
 
-fn main() -> Result<()> {
+/// The time `--time` picks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum TimeWord {
+    #[value(alias = "access", alias = "use")]
+    Atime,
+    #[value(alias = "modify")]
+    Mtime,
+}
+
+impl Args {
+    /// Which of the access and modification times to change: those picked
+    /// by `-a`, `-m`, and `--time`, or both when none are.
+    fn selected_times(&self) -> (bool, bool) {
+        let access = self.access || self.time == Some(TimeWord::Atime);
+        let modification = self.modification || self.time == Some(TimeWord::Mtime);
+        if access || modification {
+            (access, modification)
+        } else {
+            (true, true)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    common::exit::report("touch", run())
+}
+
+/// The access and modification times to give each file. `None` leaves
+/// that time as it is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Times {
+    accessed: Option<FileTime>,
+    modified: Option<FileTime>,
+}
+
+fn run() -> Result<()> {
     let args = Args::parse();
+    let times = requested_times(&args)?;
     
     for file in &args.files {
-        touch_file(file, args.no_create)
-            .with_context(|| format!("Failed to touch file: {}", file))?;
+        if args.recursive && Path::new(file).is_dir() {
+            touch_recursive(file, args.include_dirs, times)
+                .with_context(|| format!("Failed to touch directory: {}", file))?;
+        } else {
+            touch_file(file, args.no_create, times)
+                .with_context(|| format!("Failed to touch file: {}", file))?;
+        }
     }
     
     Ok(())
 }
 
-fn touch_file(path: &str, no_create: bool) -> Result<()> {
+/// The times `-t`, `-d`, or `-r` ask for, or now, for just the times
+/// `-a` and `-m` select.
+fn requested_times(args: &Args) -> Result<Times> {
+    let (accessed, modified) = if let Some(reference) = &args.reference {
+        let metadata = fs::metadata(reference)
+            .with_context(|| format!("failed to get attributes of '{}'", reference))?;
+        (
+            FileTime::from_last_access_time(&metadata),
+            FileTime::from_last_modification_time(&metadata),
+        )
+    } else {
+        let time = if let Some(stamp) = &args.stamp {
+            FileTime::from_unix_time(parse_stamp(stamp)?, 0)
+        } else if let Some(date) = &args.date {
+            FileTime::from_unix_time(common::time::parse_date(date, false)?, 0)
+        } else {
+            FileTime::now()
+        };
+        (time, time)
+    };
+    
+    let (access, modification) = args.selected_times();
+    Ok(Times {
+        accessed: access.then_some(accessed),
+        modified: modification.then_some(modified),
+    })
+}
+
+/// Parses a `-t` stamp, `[[CC]YY]MMDDhhmm[.ss]` in local time, into
+/// seconds since the epoch. Without a year, the current one is used; a
+/// two-digit year from 69 on is in the 1900s, and before that the 2000s.
+fn parse_stamp(stamp: &str) -> Result<i64> {
+    let invalid = || UsageError::new(format!("invalid date format '{}'", stamp));
+    
+    let (digits, seconds) = match stamp.split_once('.') {
+        Some((digits, seconds)) if seconds.len() == 2 => (digits, seconds),
+        Some(_) => return Err(invalid().into()),
+        None => (stamp, "00"),
+    };
+    if !digits.bytes().chain(seconds.bytes()).all(|b| b.is_ascii_digit()) {
+        return Err(invalid().into());
+    }
+    
+    let number = |range: std::ops::Range<usize>| digits[range].parse::<u32>().unwrap_or_default();
+    let (year, rest) = match digits.len() {
+        8 => (current_year(), 0),
+        10 => match number(0..2) {
+            year @ 69..=99 => (1900 + i64::from(year), 2),
+            year => (2000 + i64::from(year), 2),
+        },
+        12 => (i64::from(number(0..4)), 4),
+        _ => return Err(invalid().into()),
+    };
+    let month = number(rest..rest + 2);
+    let day = number(rest + 2..rest + 4);
+    let hour = number(rest + 4..rest + 6);
+    let minute = number(rest + 6..rest + 8);
+    let second = seconds.parse::<u32>().unwrap_or_default();
+    
+    if !(1..=12).contains(&month)
+        || day == 0
+        || day > days_in_month(year, month)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return Err(invalid().into());
+    }
+    
+    let seconds_of_day = i64::from(hour * 3600 + minute * 60 + second);
+    Ok(from_local(days_from_civil(year, month, day) * SECONDS_PER_DAY + seconds_of_day))
+}
+
+fn current_year() -> i64 {
+    let now = common::time::unix_seconds(std::time::SystemTime::now());
+    let local = now + common::time::local_zone(now).0;
+    civil_from_days(local.div_euclid(SECONDS_PER_DAY)).0
+}
+
+fn touch_file(path: &str, no_create: bool, times: Times) -> Result<()> {
     let path_obj = Path::new(path);
     
-    if path_obj.exists() {
-        // Update the modification time by opening and closing the file
-        OpenOptions::new()
-            .write(true)
-            .open(path_obj)?;
-        Ok(())
-    } else {
+    if !path_obj.exists() {
         if no_create {
             // Don't create if -c flag is set
-            Ok(())
+            return Ok(());
+        }
+        File::create(path_obj)?;
+    }
+    
+    update_times(path_obj, times)
+}
+
+/// Walks `root` and updates the timestamps of every file below it.
+/// Symlinks are touched themselves rather than their targets.
+fn touch_recursive(root: &str, include_dirs: bool, times: Times) -> Result<()> {
+    for entry in WalkDir::new(root) {
+        let entry = entry?;
+        let file_type = entry.file_type();
+        
+        if file_type.is_dir() && !include_dirs {
+            continue;
+        }
+        
+        if file_type.is_symlink() {
+            // There is no call for just one of a symlink's times, so fill
+            // in the one being kept
+            let metadata = entry.path().symlink_metadata()?;
+            filetime::set_symlink_file_times(
+                entry.path(),
+                times.accessed.unwrap_or_else(|| FileTime::from_last_access_time(&metadata)),
+                times.modified.unwrap_or_else(|| FileTime::from_last_modification_time(&metadata)),
+            )?;
         } else {
-            // Create the file
-            File::create(path_obj)?;
-            Ok(())
+            update_times(entry.path(), times)?;
         }
     }
+    
+    Ok(())
+}
+
+/// Sets the access and modification times of `path`, or just the one
+/// `times` has.
+fn update_times(path: &Path, times: Times) -> Result<()> {
+    match (times.accessed, times.modified) {
+        (Some(accessed), Some(modified)) => filetime::set_file_times(path, accessed, modified)?,
+        (Some(accessed), None) => filetime::set_file_atime(path, accessed)?,
+        (None, Some(modified)) => filetime::set_file_mtime(path, modified)?,
+        (None, None) => {}
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -57,6 +253,15 @@ mod tests {
     use std::thread;
     use std::time::Duration;
 
+    /// Both times set to now, as a plain `touch` does.
+    fn now() -> Times {
+        let now = FileTime::now();
+        Times {
+            accessed: Some(now),
+            modified: Some(now),
+        }
+    }
+
     #[test]
     fn test_create_new_file() {
         let temp_dir = env::temp_dir();
@@ -67,7 +272,7 @@ mod tests {
         
         assert!(!test_file.exists());
         
-        let result = touch_file(test_file.to_str().unwrap(), false);
+        let result = touch_file(test_file.to_str().unwrap(), false, now());
         assert!(result.is_ok());
         assert!(test_file.exists());
         
@@ -88,7 +293,7 @@ mod tests {
         
         thread::sleep(Duration::from_millis(10));
         
-        let result = touch_file(test_file.to_str().unwrap(), false);
+        let result = touch_file(test_file.to_str().unwrap(), false, now());
         assert!(result.is_ok());
         
         let metadata_after = fs::metadata(&test_file).unwrap();
@@ -100,6 +305,52 @@ mod tests {
         fs::remove_file(&test_file).unwrap();
     }
 
+    #[test]
+    fn test_touch_recursive() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("sub/deeper")).unwrap();
+        let files = [
+            root.join("top.txt"),
+            root.join("sub/middle.txt"),
+            root.join("sub/deeper/bottom.txt"),
+        ];
+        
+        let old_time = FileTime::from_unix_time(1_000_000_000, 0);
+        for file in &files {
+            File::create(file).unwrap();
+            filetime::set_file_mtime(file, old_time).unwrap();
+        }
+        filetime::set_file_mtime(root.join("sub"), old_time).unwrap();
+        
+        let result = touch_recursive(root.to_str().unwrap(), false, now());
+        assert!(result.is_ok());
+        
+        for file in &files {
+            let mtime = FileTime::from_last_modification_time(&fs::metadata(file).unwrap());
+            assert!(mtime > old_time, "{} was not touched", file.display());
+        }
+        
+        // Directories are left alone unless requested
+        let dir_mtime = FileTime::from_last_modification_time(&fs::metadata(root.join("sub")).unwrap());
+        assert_eq!(dir_mtime, old_time);
+    }
+
+    #[test]
+    fn test_touch_recursive_include_dirs() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let sub = temp_dir.path().join("sub");
+        fs::create_dir(&sub).unwrap();
+        
+        let old_time = FileTime::from_unix_time(1_000_000_000, 0);
+        filetime::set_file_mtime(&sub, old_time).unwrap();
+        
+        touch_recursive(temp_dir.path().to_str().unwrap(), true, now()).unwrap();
+        
+        let dir_mtime = FileTime::from_last_modification_time(&fs::metadata(&sub).unwrap());
+        assert!(dir_mtime > old_time);
+    }
+
     #[test]
     fn test_no_create_flag() {
         let temp_dir = env::temp_dir();
@@ -110,9 +361,34 @@ mod tests {
         
         assert!(!test_file.exists());
         
-        let result = touch_file(test_file.to_str().unwrap(), true);
+        let result = touch_file(test_file.to_str().unwrap(), true, now());
         assert!(result.is_ok());
         assert!(!test_file.exists()); // Should NOT be created
     }
-}
 
+    #[test]
+    fn test_parse_stamp_lengths() {
+        let base = |year| {
+            from_local(days_from_civil(year, 1, 2) * SECONDS_PER_DAY + 3 * 3600 + 4 * 60)
+        };
+        assert_eq!(parse_stamp("202401020304").unwrap(), base(2024));
+        assert_eq!(parse_stamp("202401020304.05").unwrap(), base(2024) + 5);
+        assert_eq!(parse_stamp("2401020304").unwrap(), base(2024));
+        assert_eq!(parse_stamp("6901020304").unwrap(), base(1969));
+        assert_eq!(parse_stamp("01020304").unwrap(), base(current_year()));
+    }
+
+    #[test]
+    fn test_parse_stamp_rejects_bad_stamps() {
+        for stamp in [
+            "2024010203",
+            "202413020304",
+            "202402300304",
+            "202401022404",
+            "202401020304.5",
+            "2024-1020304",
+        ] {
+            assert!(parse_stamp(stamp).is_err(), "{} was accepted", stamp);
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use assert_cmd::Command;
+use filetime::FileTime;
+use std::fs;
+use tempfile::TempDir;
+
+fn modified(path: &std::path::Path) -> i64 {
+    FileTime::from_last_modification_time(&fs::metadata(path).unwrap()).unix_seconds()
+}
+
+#[test]
+fn test_touch_t_sets_stamp() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("file.txt");
+
+    let mut cmd = Command::cargo_bin("touch").unwrap();
+    cmd.env("TZ", "UTC")
+        .arg("-t")
+        .arg("202401020304.05")
+        .arg(&file);
+    cmd.assert().success();
+
+    // 2024-01-02 03:04:05 UTC
+    assert_eq!(modified(&file), 1_704_164_645);
+}
+
+#[test]
+fn test_touch_d_sets_epoch_seconds() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("file.txt");
+    fs::write(&file, "data").unwrap();
+
+    let mut cmd = Command::cargo_bin("touch").unwrap();
+    cmd.arg("-d").arg("@1700000000").arg(&file);
+    cmd.assert().success();
+
+    assert_eq!(modified(&file), 1_700_000_000);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "data");
+}
+
+#[test]
+fn test_touch_d_takes_iso_dates() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("file.txt");
+
+    let mut cmd = Command::cargo_bin("touch").unwrap();
+    cmd.arg("-d").arg("2024-01-02 03:04:05Z").arg(&file);
+    cmd.assert().success();
+
+    assert_eq!(modified(&file), 1_704_164_645);
+}
+
+#[test]
+fn test_touch_t_and_d_conflict() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("file.txt");
+
+    let mut cmd = Command::cargo_bin("touch").unwrap();
+    cmd.arg("-t")
+        .arg("202401020304")
+        .arg("-d")
+        .arg("@0")
+        .arg(&file);
+    cmd.assert().code(2);
+    assert!(!file.exists());
+}
+
+#[test]
+fn test_touch_r_copies_reference_times() {
+    let temp_dir = TempDir::new().unwrap();
+    let reference = temp_dir.path().join("reference.txt");
+    let target = temp_dir.path().join("target.txt");
+    fs::write(&reference, "").unwrap();
+    filetime::set_file_times(
+        &reference,
+        FileTime::from_unix_time(1_600_000_000, 0),
+        FileTime::from_unix_time(1_500_000_000, 0),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("touch").unwrap();
+    cmd.arg("-r").arg(&reference).arg(&target);
+    cmd.assert().success();
+
+    let metadata = fs::metadata(&target).unwrap();
+    assert_eq!(
+        FileTime::from_last_access_time(&metadata).unix_seconds(),
+        1_600_000_000
+    );
+    assert_eq!(modified(&target), 1_500_000_000);
+}
+
+#[test]
+fn test_touch_r_needs_an_existing_reference() {
+    let temp_dir = TempDir::new().unwrap();
+    let target = temp_dir.path().join("target.txt");
+
+    let mut cmd = Command::cargo_bin("touch").unwrap();
+    cmd.arg("-r")
+        .arg(temp_dir.path().join("missing"))
+        .arg(&target);
+    cmd.assert()
+        .code(1)
+        .stderr(predicates::str::contains("failed to get attributes of"));
+    assert!(!target.exists());
+}
+
+fn accessed(path: &std::path::Path) -> i64 {
+    FileTime::from_last_access_time(&fs::metadata(path).unwrap()).unix_seconds()
+}
+
+fn file_with_old_times(temp_dir: &TempDir) -> std::path::PathBuf {
+    let file = temp_dir.path().join("file.txt");
+    fs::write(&file, "").unwrap();
+    filetime::set_file_times(
+        &file,
+        FileTime::from_unix_time(1_000_000_000, 0),
+        FileTime::from_unix_time(1_100_000_000, 0),
+    )
+    .unwrap();
+    file
+}
+
+#[test]
+fn test_touch_a_leaves_mtime_alone() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = file_with_old_times(&temp_dir);
+
+    let mut cmd = Command::cargo_bin("touch").unwrap();
+    cmd.arg("-a").arg(&file);
+    cmd.assert().success();
+
+    assert!(accessed(&file) > 1_100_000_000);
+    assert_eq!(modified(&file), 1_100_000_000);
+}
+
+#[test]
+fn test_touch_m_leaves_atime_alone() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = file_with_old_times(&temp_dir);
+
+    let mut cmd = Command::cargo_bin("touch").unwrap();
+    cmd.arg("--time=modify")
+        .arg("-d")
+        .arg("@1200000000")
+        .arg(&file);
+    cmd.assert().success();
+
+    assert_eq!(accessed(&file), 1_000_000_000);
+    assert_eq!(modified(&file), 1_200_000_000);
+}
+
+#[test]
+fn test_touch_r_with_m_copies_only_mtime() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = file_with_old_times(&temp_dir);
+    let reference = temp_dir.path().join("reference.txt");
+    fs::write(&reference, "").unwrap();
+    filetime::set_file_times(
+        &reference,
+        FileTime::from_unix_time(1_600_000_000, 0),
+        FileTime::from_unix_time(1_500_000_000, 0),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("touch").unwrap();
+    cmd.arg("-r").arg(&reference).arg("-m").arg(&file);
+    cmd.assert().success();
+
+    assert_eq!(accessed(&file), 1_000_000_000);
+    assert_eq!(modified(&file), 1_500_000_000);
+}
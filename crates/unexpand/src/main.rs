@@ -0,0 +1,149 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use common::tabs::TabStops;
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+
+#[derive(Parser, Debug)]
+#[command(name = "unexpand")]
+#[command(about = "Convert spaces to tabs", long_about = None)]
+#[command(version)]
+struct Args {
+    /// Convert all blanks, not just leading ones
+    #[arg(short = 'a', long = "all")]
+    all: bool,
+
+    /// Tab width N, or a comma-separated list of tab stop columns
+    #[arg(short = 't', long = "tabs", default_value = "8")]
+    tabs: String,
+
+    /// Files to process (use '-' for stdin)
+    #[arg(default_value = "-")]
+    files: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    common::exit::report("unexpand", run())
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse();
+    let stops = TabStops::parse(&args.tabs)?;
+    let mut stdout = common::io::stdout_writer();
+
+    for file in &args.files {
+        let reader = common::io::open_input(file)
+            .with_context(|| format!("Failed to open file: {}", file))?;
+        unexpand_reader(reader, &mut stdout, &stops, args.all)
+            .with_context(|| format!("Failed to process file: {}", file))?;
+    }
+
+    stdout.flush()?;
+    Ok(())
+}
+
+fn unexpand_reader<R: BufRead, W: Write>(
+    mut reader: R,
+    writer: &mut W,
+    stops: &TabStops,
+    all: bool,
+) -> io::Result<()> {
+    let mut line = Vec::new();
+
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        writer.write_all(&unexpand_line(&line, stops, all))?;
+        line.clear();
+    }
+
+    Ok(())
+}
+
+/// Replaces runs of blanks that reach a tab stop with a tab.
+///
+/// Only leading blanks are converted unless `all` is set. A single space
+/// that happens to end on a tab stop is left alone.
+fn unexpand_line(line: &[u8], stops: &TabStops, all: bool) -> Vec<u8> {
+    let mut output = Vec::with_capacity(line.len());
+    let mut column = 0;
+    let mut pending_spaces = 0;
+
+    for (i, &byte) in line.iter().enumerate() {
+        match byte {
+            b' ' => {
+                column += 1;
+                pending_spaces += 1;
+                if stops.is_stop(column) {
+                    output.push(if pending_spaces > 1 { b'\t' } else { b' ' });
+                    pending_spaces = 0;
+                }
+            }
+            b'\t' => {
+                column = stops.next_stop(column).unwrap_or(column + 1);
+                output.push(b'\t');
+                pending_spaces = 0;
+            }
+            _ => {
+                output.resize(output.len() + pending_spaces, b' ');
+                pending_spaces = 0;
+
+                if !all {
+                    output.extend_from_slice(&line[i..]);
+                    return output;
+                }
+
+                output.push(byte);
+                if byte == b'\n' {
+                    column = 0;
+                } else if byte & 0xC0 != 0x80 {
+                    column += 1;
+                }
+            }
+        }
+    }
+
+    output.resize(output.len() + pending_spaces, b' ');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unexpand_leading_spaces() {
+        let stops = TabStops::Every(4);
+        assert_eq!(unexpand_line(b"        x", &stops, false), b"\t\tx");
+    }
+
+    #[test]
+    fn test_unexpand_partial_leading_run() {
+        let stops = TabStops::Every(4);
+        assert_eq!(unexpand_line(b"      x", &stops, false), b"\t  x");
+    }
+
+    #[test]
+    fn test_unexpand_leaves_inner_spaces_by_default() {
+        let stops = TabStops::Every(4);
+        assert_eq!(unexpand_line(b"    a   b\n", &stops, false), b"\ta   b\n");
+    }
+
+    #[test]
+    fn test_unexpand_all() {
+        let stops = TabStops::Every(4);
+        assert_eq!(unexpand_line(b"    ab  c\n", &stops, true), b"\tab\tc\n");
+    }
+
+    #[test]
+    fn test_unexpand_single_space_at_stop() {
+        let stops = TabStops::Every(4);
+        assert_eq!(unexpand_line(b"abc d", &stops, true), b"abc d");
+    }
+
+    #[test]
+    fn test_unexpand_reader() {
+        let mut output = Vec::new();
+        unexpand_reader(&b"    a\n        b"[..], &mut output, &TabStops::Every(4), false)
+            .unwrap();
+        assert_eq!(output, b"\ta\n\t\tb");
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::Result;
+use clap::Parser;
+use std::io::Write;
+use std::process::ExitCode;
+
+#[derive(Parser, Debug)]
+#[command(name = "xattr-tool")]
+#[command(about = "List, get, set, and remove extended file attributes", long_about = None)]
+#[command(version)]
+struct Args {
+    /// List every attribute with its value (the default)
+    #[arg(short = 'l', long = "list", conflicts_with_all = ["name", "remove"])]
+    list: bool,
+
+    /// Print the attribute NAME, or set it with -v
+    #[arg(
+        short = 'n',
+        long = "name",
+        value_name = "NAME",
+        conflicts_with = "remove"
+    )]
+    name: Option<String>,
+
+    /// Set the attribute given with -n to VALUE
+    #[arg(short = 'v', long = "value", value_name = "VALUE", requires = "name")]
+    value: Option<String>,
+
+    /// Remove the attribute NAME
+    #[arg(short = 'x', long = "remove", value_name = "NAME")]
+    remove: Option<String>,
+
+    /// Files to operate on
+    #[arg(required = true)]
+    files: Vec<String>,
+}
+
+/// The single operation requested on the command line.
+#[derive(Debug, PartialEq)]
+enum Action {
+    List,
+    Get(String),
+    Set(String, String),
+    Remove(String),
+}
+
+impl Action {
+    fn from_args(args: &Args) -> Self {
+        match (&args.name, &args.value, &args.remove) {
+            (_, _, Some(name)) => Action::Remove(name.clone()),
+            (Some(name), Some(value), _) => Action::Set(name.clone(), value.clone()),
+            (Some(name), None, _) => Action::Get(name.clone()),
+            (None, _, None) => Action::List,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    common::exit::report("xattr-tool", run())
+}
+
+#[cfg(unix)]
+fn run() -> Result<()> {
+    let args = Args::parse();
+
+    if !xattr::SUPPORTED_PLATFORM {
+        anyhow::bail!("extended attributes are not supported on this platform");
+    }
+
+    let action = Action::from_args(&args);
+    let mut stdout = common::io::stdout_writer();
+
+    for (index, file) in args.files.iter().enumerate() {
+        // Blank line between the per-file blocks, like getfattr
+        if index > 0 && matches!(action, Action::List | Action::Get(_)) {
+            writeln!(stdout)?;
+        }
+        attributes::run(&action, file, &mut stdout)?;
+    }
+
+    stdout.flush()?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn run() -> Result<()> {
+    let _ = Args::parse();
+    anyhow::bail!("extended attributes are not supported on this platform")
+}
+
+/// Quotes an attribute value for display, escaping bytes that aren't
+/// printable ASCII as `\ooo` octal.
+fn format_value(value: &[u8]) -> String {
+    let mut output = String::from("\"");
+
+    for &byte in value {
+        match byte {
+            b'"' | b'\\' => output.push_str(&format!("\\{}", byte as char)),
+            0x20..=0x7e => output.push(byte as char),
+            _ => output.push_str(&format!("\\{:03o}", byte)),
+        }
+    }
+
+    output.push('"');
+    output
+}
+
+#[cfg(unix)]
+mod attributes {
+    use super::{format_value, Action};
+    use anyhow::{Context, Result};
+    use std::io::Write;
+
+    pub fn run<W: Write>(action: &Action, file: &str, writer: &mut W) -> Result<()> {
+        match action {
+            Action::List => {
+                writeln!(writer, "# file: {}", file)?;
+                for (name, value) in list(file)? {
+                    writeln!(writer, "{}={}", name, format_value(&value))?;
+                }
+            }
+            Action::Get(name) => {
+                let value = xattr::get(file, name)
+                    .with_context(|| format!("{}: failed to read {}", file, name))?
+                    .ok_or_else(|| anyhow::anyhow!("{}: {}: No such attribute", file, name))?;
+                writeln!(writer, "# file: {}", file)?;
+                writeln!(writer, "{}={}", name, format_value(&value))?;
+            }
+            Action::Set(name, value) => {
+                xattr::set(file, name, value.as_bytes())
+                    .with_context(|| format!("{}: failed to set {}", file, name))?;
+            }
+            Action::Remove(name) => {
+                xattr::remove(file, name)
+                    .with_context(|| format!("{}: failed to remove {}", file, name))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Every attribute of `file` with its value, sorted by name.
+    fn list(file: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        let names =
+            xattr::list(file).with_context(|| format!("{}: failed to list attributes", file))?;
+
+        let mut attributes = Vec::new();
+        for name in names {
+            // An attribute removed since listing is simply skipped
+            if let Some(value) = xattr::get(file, &name)? {
+                attributes.push((name.to_string_lossy().into_owned(), value));
+            }
+        }
+
+        attributes.sort();
+        Ok(attributes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(argv: &[&str]) -> Action {
+        let args = Args::try_parse_from(argv).unwrap();
+        Action::from_args(&args)
+    }
+
+    #[test]
+    fn test_action_from_args() {
+        assert_eq!(parse(&["xattr-tool", "f"]), Action::List);
+        assert_eq!(parse(&["xattr-tool", "-l", "f"]), Action::List);
+        assert_eq!(
+            parse(&["xattr-tool", "-n", "user.a", "f"]),
+            Action::Get("user.a".to_string())
+        );
+        assert_eq!(
+            parse(&["xattr-tool", "-n", "user.a", "-v", "1", "f"]),
+            Action::Set("user.a".to_string(), "1".to_string())
+        );
+        assert_eq!(
+            parse(&["xattr-tool", "-x", "user.a", "f"]),
+            Action::Remove("user.a".to_string())
+        );
+    }
+
+    #[test]
+    fn test_conflicting_actions_rejected() {
+        assert!(Args::try_parse_from(["xattr-tool", "-l", "-x", "user.a", "f"]).is_err());
+        assert!(Args::try_parse_from(["xattr-tool", "-v", "1", "f"]).is_err());
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(b"hello"), "\"hello\"");
+        assert_eq!(format_value(b"a\"b\\"), "\"a\\\"b\\\\\"");
+        assert_eq!(format_value(b"x\0\n"), "\"x\\000\\012\"");
+    }
+}
@@ -0,0 +1,60 @@
+#![cfg(unix)]
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs::File;
+use tempfile::TempDir;
+
+#[test]
+fn test_set_list_get_remove() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("file.txt");
+    File::create(&file).unwrap();
+
+    // Skip on filesystems without user xattr support
+    if xattr::set(&file, "user.probe", b"").is_err() {
+        return;
+    }
+    xattr::remove(&file, "user.probe").unwrap();
+
+    let mut cmd = Command::cargo_bin("xattr-tool").unwrap();
+    cmd.arg("-n")
+        .arg("user.comment")
+        .arg("-v")
+        .arg("hello")
+        .arg(&file);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("xattr-tool").unwrap();
+    cmd.arg("-l").arg(&file);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("user.comment=\"hello\"\n"));
+
+    let mut cmd = Command::cargo_bin("xattr-tool").unwrap();
+    cmd.arg("-n").arg("user.comment").arg(&file);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::ends_with("user.comment=\"hello\"\n"));
+
+    let mut cmd = Command::cargo_bin("xattr-tool").unwrap();
+    cmd.arg("-x").arg("user.comment").arg(&file);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("xattr-tool").unwrap();
+    cmd.arg("-l").arg(&file);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("user.comment").not());
+}
+
+#[test]
+fn test_get_missing_attribute_fails() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("file.txt");
+    File::create(&file).unwrap();
+
+    let mut cmd = Command::cargo_bin("xattr-tool").unwrap();
+    cmd.arg("-n").arg("user.missing").arg(&file);
+    cmd.assert().failure();
+}